@@ -0,0 +1,61 @@
+//! Proc macros for `schema`; use them through its re-exports.
+
+use proc_macro::TokenStream;
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, Fields, ItemStruct, LitInt};
+
+/// Register a unit struct implementing `Solver` as the solution for a given
+/// puzzle.
+///
+/// ```ignore
+/// #[schema::solver(year = 2023, day = 1)]
+/// pub struct Day01;
+/// ```
+#[proc_macro_attribute]
+pub fn solver(args: TokenStream, item: TokenStream) -> TokenStream {
+    let mut year: Option<LitInt> = None;
+    let mut day: Option<LitInt> = None;
+
+    let parser = syn::meta::parser(|meta| {
+        if meta.path.is_ident("year") {
+            year = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("day") {
+            day = Some(meta.value()?.parse()?);
+        } else {
+            return Err(meta.error("expected `year` or `day`"));
+        }
+
+        Ok(())
+    });
+
+    parse_macro_input!(args with parser);
+    let item = parse_macro_input!(item as ItemStruct);
+
+    let (Some(year), Some(day)) = (year, day) else {
+        return syn::Error::new_spanned(&item.ident, "#[solver] needs both `year = ..` and `day = ..`")
+            .to_compile_error()
+            .into();
+    };
+
+    if !matches!(item.fields, Fields::Unit) {
+        return syn::Error::new_spanned(&item.fields, "#[solver] can only register unit structs")
+            .to_compile_error()
+            .into();
+    }
+
+    let ident = &item.ident;
+    let register = format_ident!("__REGISTER_{}", ident.to_string().to_uppercase());
+
+    quote! {
+        #item
+
+        #[::schema::linkme::distributed_slice(::schema::registry::SOLVERS)]
+        #[linkme(crate = ::schema::linkme)]
+        static #register: ::schema::registry::Registration = ::schema::registry::Registration {
+            year: #year,
+            day: #day,
+            solver: &#ident,
+        };
+    }
+    .into()
+}
@@ -0,0 +1,163 @@
+//! Counting what a part allocates.
+//!
+//! Install [`CountingAlloc`] as the `#[global_allocator]` and every
+//! [`Solution`](crate::Solution) carries the [`Allocations`] its part made.
+//! Counts are kept per thread, so parts running side by side don't muddle
+//! each other's; anything a part hands off to threads of its own is missed.
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use serde::{Deserialize, Serialize};
+
+/// What one part allocated while it ran.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Allocations {
+    /// Allocations made, reallocations included.
+    pub count: u64,
+    /// Bytes asked for over all of them.
+    pub bytes: u64,
+    /// Most bytes held at once, over what was held when the part started.
+    pub peak: u64,
+}
+
+struct Counters {
+    count: Cell<u64>,
+    bytes: Cell<u64>,
+    /// Can go negative on a thread that frees what another allocated.
+    live: Cell<i64>,
+    peak: Cell<i64>,
+}
+
+thread_local! {
+    static COUNTERS: Counters = const {
+        Counters { count: Cell::new(0), bytes: Cell::new(0), live: Cell::new(0), peak: Cell::new(0) }
+    };
+}
+
+/// Set once [`CountingAlloc`] has seen an allocation, so [`measure`] knows
+/// whether its counts mean anything.
+static INSTALLED: AtomicBool = AtomicBool::new(false);
+
+/// The system allocator, counting on the side.
+pub struct CountingAlloc;
+
+impl CountingAlloc {
+    fn record(&self, allocated: usize, freed: usize) {
+        INSTALLED.store(true, Ordering::Relaxed);
+
+        // Fails only while the thread is being torn down; those go uncounted.
+        let _ = COUNTERS.try_with(|c| {
+            if allocated > 0 {
+                c.count.set(c.count.get() + 1);
+                c.bytes.set(c.bytes.get() + allocated as u64);
+            }
+
+            let live = c.live.get() + allocated as i64 - freed as i64;
+            c.live.set(live);
+            c.peak.set(c.peak.get().max(live));
+        });
+    }
+}
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+
+        if !ptr.is_null() {
+            self.record(layout.size(), 0);
+        }
+
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+
+        if !ptr.is_null() {
+            self.record(layout.size(), 0);
+        }
+
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        self.record(0, layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new = System.realloc(ptr, layout, new_size);
+
+        if !new.is_null() {
+            self.record(new_size, layout.size());
+        }
+
+        new
+    }
+}
+
+/// Run `f`, counting what it allocates on this thread, if [`CountingAlloc`]
+/// is installed.
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, Option<Allocations>) {
+    let start = COUNTERS.with(|c| {
+        let outer_peak = c.peak.replace(c.live.get());
+        (c.count.get(), c.bytes.get(), c.live.get(), outer_peak)
+    });
+
+    let out = f();
+
+    let allocations = COUNTERS.with(|c| {
+        let (count, bytes, live, outer_peak) = start;
+        let peak = c.peak.get();
+
+        // So an enclosing `measure` still sees this peak.
+        c.peak.set(peak.max(outer_peak));
+
+        Allocations {
+            count: c.count.get() - count,
+            bytes: c.bytes.get() - bytes,
+            peak: (peak - live).max(0) as u64,
+        }
+    });
+
+    (out, INSTALLED.load(Ordering::Relaxed).then_some(allocations))
+}
+
+#[cfg(test)]
+mod test {
+    use super::{measure, CountingAlloc};
+
+    #[global_allocator]
+    static ALLOC: CountingAlloc = CountingAlloc;
+
+    #[test]
+    fn counts() {
+        let (len, outer) = measure(|| {
+            let big = vec![0u8; 4096];
+            drop(big);
+
+            let ((), inner) = measure(|| {
+                let small: Vec<u64> = (0..16).collect();
+                assert_eq!(small.len(), 16);
+            });
+
+            let inner = inner.unwrap();
+            assert_eq!((inner.count, inner.bytes, inner.peak), (1, 128, 128));
+
+            let mut grown = Vec::with_capacity(1);
+            grown.extend_from_slice(&[1u8; 100]);
+            grown.len()
+        });
+
+        let outer = outer.unwrap();
+
+        assert_eq!(len, 100);
+        assert!(outer.count >= 4, "{outer:?}");
+        assert!(outer.bytes >= 4096 + 128 + 100, "{outer:?}");
+        assert_eq!(outer.peak, 4096);
+    }
+}
@@ -1,26 +1,197 @@
-/// Taken from `https://github.com/Basicprogrammer10/advent-of-code/blob/main/common/src/answer.rs`
+//! Taken from `https://github.com/Basicprogrammer10/advent-of-code/blob/main/common/src/answer.rs`
 
-use std::fmt::{self, Display};
+use std::{convert::Infallible, fmt::{self, Display}, str::FromStr};
 
-#[derive(Debug, PartialEq)]
+use num::{BigInt, BigUint, ToPrimitive};
+use serde::{Deserialize, Serialize};
+
+use crate::Bitmap;
+
+/// Serialized externally tagged, e.g. `{"Number": 42}`, so every variant
+/// survives a round trip through JSON.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Answer {
     String(String),
     Number(u64),
+    Signed(i128),
+    #[serde(with = "big_string")]
+    Big(BigInt),
     Float(f64),
+    /// Letters drawn on a pixel grid.
+    Grid(Bitmap),
     Unimplemented,
 }
 
+impl Answer {
+    /// Absolute tolerance [`Answer::matches`] allows between floats.
+    pub const DEFAULT_TOLERANCE: f64 = 1e-9;
+
+    /// The variant's name in lower case, e.g. `"number"`, for reports.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Answer::String(_) => "string",
+            Answer::Number(_) => "number",
+            Answer::Signed(_) => "signed",
+            Answer::Big(_) => "big",
+            Answer::Float(_) => "float",
+            Answer::Grid(_) => "grid",
+            Answer::Unimplemented => "unimplemented",
+        }
+    }
+
+    /// Loose comparison for checking answers against recorded ones.
+    ///
+    /// Both sides are [normalized](Answer::normalized) first, so
+    /// `Number(42)` matches `String("42")` and a grid matches the letters it
+    /// spells. Floats, or a float and an integer, match within
+    /// [`Answer::DEFAULT_TOLERANCE`].
+    pub fn matches(&self, other: &Answer) -> bool {
+        self.matches_within(other, Self::DEFAULT_TOLERANCE)
+    }
+
+    /// [`Answer::matches`] with an explicit absolute float tolerance.
+    pub fn matches_within(&self, other: &Answer, tolerance: f64) -> bool {
+        let (a, b) = (self.normalized(), other.normalized());
+
+        match (&a, &b) {
+            (Answer::Float(x), _) | (_, Answer::Float(x)) => {
+                let y = if matches!(a, Answer::Float(_)) { b.float() } else { a.float() };
+
+                y.is_some_and(|y| (x - y).abs() <= tolerance || x == &y)
+            },
+            _ => a == b,
+        }
+    }
+
+    /// The canonical form of this answer: text is trimmed and re-read with
+    /// [`FromStr`], grids become the string they spell, and integers use the
+    /// narrowest variant that holds them.
+    pub fn normalized(&self) -> Answer {
+        match self {
+            Answer::String(s) => match s.parse() {
+                Ok(Answer::String(s)) => Answer::String(s),
+                Ok(other) => other.normalized(),
+                Err(e) => match e {},
+            },
+            Answer::Grid(g) => match g.ocr() {
+                Some(text) => Answer::String(text),
+                None => self.clone(),
+            },
+            _ => match self.integer() {
+                Some(n) => Self::narrow(n),
+                None => self.clone(),
+            },
+        }
+    }
+
+    fn narrow(n: BigInt) -> Answer {
+        if let Some(n) = n.to_u64() {
+            Answer::Number(n)
+        } else if let Some(n) = n.to_i128() {
+            Answer::Signed(n)
+        } else {
+            Answer::Big(n)
+        }
+    }
+
+    fn float(&self) -> Option<f64> {
+        match self {
+            Answer::Float(f) => Some(*f),
+            _ => self.integer().and_then(|n| n.to_f64()),
+        }
+    }
+
+    /// The integer value of any of the integer variants.
+    fn integer(&self) -> Option<BigInt> {
+        match self {
+            Answer::Number(n) => Some((*n).into()),
+            Answer::Signed(n) => Some((*n).into()),
+            Answer::Big(n) => Some(n.clone()),
+            _ => None,
+        }
+    }
+}
+
+/// Integer variants compare by value, so `Number(5) == Signed(5)`; anything
+/// else must be the same variant. Reading a `Grid` as the `String` it spells
+/// is left to [`Answer::matches`], so that `==` stays transitive.
+impl PartialEq for Answer {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Answer::Number(a), Answer::Number(b)) => a == b,
+            (Answer::Number(a), Answer::Signed(b))
+            | (Answer::Signed(b), Answer::Number(a)) => i128::from(*a) == *b,
+            (Answer::Signed(a), Answer::Signed(b)) => a == b,
+            (Answer::String(a), Answer::String(b)) => a == b,
+            (Answer::Float(a), Answer::Float(b)) => a == b,
+            (Answer::Grid(a), Answer::Grid(b)) => a == b,
+            (Answer::Unimplemented, Answer::Unimplemented) => true,
+            _ => match (self.integer(), other.integer()) {
+                (Some(a), Some(b)) => a == b,
+                _ => false,
+            },
+        }
+    }
+}
+
 impl Display for Answer {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Answer::String(s) => write!(f, "{s}"),
             Answer::Number(n) => write!(f, "{n}"),
-            Answer::Float(n) => write!(f, "{n}"),
+            Answer::Signed(n) => write!(f, "{n}"),
+            Answer::Big(n) => write!(f, "{n}"),
+            // `{:?}` keeps the decimal point, so `3.0` doesn't read back as
+            // an integer.
+            Answer::Float(n) => write!(f, "{n:?}"),
+            // A grid that isn't letters is drawn in `#`/`.` on lines of its
+            // own, which reads back as the same grid.
+            Answer::Grid(g) => match g.ocr() {
+                Some(text) => write!(f, "{text}"),
+                None => write!(f, "\n{}", String::from(g.clone())),
+            },
             Answer::Unimplemented => write!(f, "Unimplemented"),
         }
     }
 }
 
+/// Reads back anything [`Display`] writes: integers become the narrowest
+/// integer variant, decimals become `Float`, a `#`/`.` picture becomes a
+/// `Grid` and anything else is kept as a `String`.
+impl FromStr for Answer {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let t = s.trim();
+
+        if t == "Unimplemented" {
+            return Ok(Answer::Unimplemented);
+        }
+
+        if let Ok(n) = t.parse::<BigInt>() {
+            return Ok(Self::narrow(n));
+        }
+
+        // Keep words like "inf" and "NaN" as text.
+        if t.bytes().all(|b| b.is_ascii_digit() || b"+-.eE".contains(&b)) {
+            if let Ok(f) = t.parse::<f64>() {
+                return Ok(Answer::Float(f));
+            }
+        }
+
+        // Display starts a grid on a new line, so one row is still a grid.
+        let picture = t.contains('\n') || s.starts_with('\n');
+
+        if picture && !t.is_empty() && t.bytes().all(|b| b"#.\n".contains(&b)) {
+            if let Ok(g) = t.parse::<Bitmap>() {
+                return Ok(Answer::Grid(g));
+            }
+        }
+
+        Ok(Answer::String(t.to_string()))
+    }
+}
+
 impl From<String> for Answer {
     fn from(s: String) -> Self {
         Self::String(s)
@@ -33,6 +204,35 @@ impl From<&str> for Answer {
     }
 }
 
+impl From<Bitmap> for Answer {
+    fn from(g: Bitmap) -> Self {
+        Self::Grid(g)
+    }
+}
+
+impl From<u128> for Answer {
+    fn from(n: u128) -> Self {
+        match u64::try_from(n) {
+            Ok(n) => Self::Number(n),
+            Err(_) => Self::Big(n.into()),
+        }
+    }
+}
+
+impl From<BigInt> for Answer {
+    fn from(n: BigInt) -> Self {
+        Self::Big(n)
+    }
+}
+
+impl From<BigUint> for Answer {
+    fn from(n: BigUint) -> Self {
+        Self::Big(n.into())
+    }
+}
+
+/// Every source type here is no wider than its target, so the casts are
+/// lossless.
 macro_rules! answer_impl {
     ($answer:ident, $answer_type:ty, { $($type:ty),* }) => {
         $(impl From<$type> for Answer {
@@ -45,10 +245,139 @@ macro_rules! answer_impl {
 
 answer_impl!(
     Number, u64,
-    { u8, u16, u32, u64, usize, i8, i16, i32, i64, isize }
+    { u8, u16, u32, u64, usize }
+);
+
+answer_impl!(
+    Signed, i128,
+    { i8, i16, i32, i64, i128, isize }
 );
 
 answer_impl!(
     Float, f64,
     { f32, f64 }
-);
\ No newline at end of file
+);
+
+/// `BigInt`s are written as decimal strings, since JSON numbers can't
+/// hold them.
+mod big_string {
+    use num::BigInt;
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(n: &BigInt, s: S) -> Result<S::Ok, S::Error> {
+        s.collect_str(n)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<BigInt, D::Error> {
+        String::deserialize(d)?.parse().map_err(D::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use indoc::indoc;
+    use num::BigInt;
+
+    use super::Answer;
+
+    #[test]
+    fn negative() {
+        let a = Answer::from(-114i64);
+        assert_eq!(a, Answer::Signed(-114));
+        assert_eq!(a.to_string(), "-114");
+        assert_ne!(a, Answer::Number(114));
+    }
+
+    #[test]
+    fn mixed_integers() {
+        assert_eq!(Answer::Number(5), Answer::Signed(5));
+        assert_eq!(Answer::Signed(5), Answer::Big(BigInt::from(5)));
+        assert_eq!(Answer::from(u128::MAX), Answer::Big(BigInt::from(u128::MAX)));
+        assert_ne!(Answer::Number(5), Answer::String("5".into()));
+    }
+
+    #[test]
+    fn wide() {
+        let big: BigInt = BigInt::from(i128::MIN) * 4;
+        assert_eq!(Answer::from(big.clone()).to_string(), big.to_string());
+        assert_eq!(Answer::from(i128::MIN).to_string(), i128::MIN.to_string());
+        assert_eq!(Answer::from(u64::MAX as u128), Answer::Number(u64::MAX));
+    }
+
+    #[test]
+    fn grid() {
+        let a = Answer::Grid(indoc! {"
+            #..#.###.
+            #..#..#..
+            ####..#..
+            #..#..#..
+            #..#..#..
+            #..#.###.
+        "}.parse().unwrap());
+
+        assert_eq!(a.to_string(), "HI");
+        assert!(a.matches(&Answer::from("HI")));
+        assert!(!a.matches(&Answer::from("HJ")));
+
+        // The same letters drawn wider are a different grid.
+        let wide = Answer::Grid(indoc! {"
+            #..#..###..
+            #..#...#...
+            ####...#...
+            #..#...#...
+            #..#...#...
+            #..#..###..
+        "}.parse().unwrap());
+
+        assert!(wide.matches(&a));
+        assert_ne!(wide, a);
+        assert_ne!(a, Answer::from("HI"));
+    }
+
+    #[test]
+    fn from_text() {
+        assert_eq!("42".parse::<Answer>().unwrap(), Answer::Number(42));
+        assert_eq!(" -7\n".parse::<Answer>().unwrap(), Answer::Signed(-7));
+        assert_eq!("2.5".parse::<Answer>().unwrap(), Answer::Float(2.5));
+        assert_eq!("inf".parse::<Answer>().unwrap(), Answer::from("inf"));
+        assert_eq!("FGH".parse::<Answer>().unwrap(), Answer::from("FGH"));
+
+        let huge = "1".repeat(50);
+        assert_eq!(huge.parse::<Answer>().unwrap(), Answer::Big(huge.parse().unwrap()));
+    }
+
+    #[test]
+    fn round_trip() {
+        let answers = [
+            Answer::from("HI"),
+            Answer::Number(42),
+            Answer::Signed(-3),
+            Answer::Big(BigInt::from(u128::MAX) * 7),
+            Answer::Float(0.1),
+            Answer::Float(3.0),
+            Answer::Float(-2.5e20),
+            Answer::Grid("#.#\n.#.".parse().unwrap()),
+            Answer::Grid("#.#".parse().unwrap()),
+            Answer::Unimplemented,
+        ];
+
+        for a in answers {
+            let json = serde_json::to_string(&a).unwrap();
+            assert_eq!(serde_json::from_str::<Answer>(&json).unwrap(), a, "{json}");
+
+            let text = a.to_string();
+            assert_eq!(text.parse::<Answer>().unwrap(), a, "{text:?}");
+        }
+    }
+
+    #[test]
+    fn matches() {
+        assert!(Answer::Number(42).matches(&Answer::from("42")));
+        assert!(Answer::from(" -3 ").matches(&Answer::Signed(-3)));
+        assert!(Answer::Float(0.1 + 0.2).matches(&Answer::Float(0.3)));
+        assert!(Answer::Float(3.0).matches(&Answer::Number(3)));
+        assert!(!Answer::Float(3.01).matches(&Answer::Number(3)));
+        assert!(Answer::Float(3.01).matches_within(&Answer::Number(3), 0.1));
+        assert!(!Answer::Number(42).matches(&Answer::from("forty-two")));
+    }
+}
@@ -0,0 +1,107 @@
+//! Shim for solvers still written against the old out-parameter API.
+//!
+//! Wrap one in [`Legacy`] to drop it into a `&[&dyn DynSolver]` table next
+//! to migrated days.
+
+use std::{sync::{Arc, Mutex}, time::Duration};
+
+use crate::{Answer, BoxError, Context, Solver};
+
+/// The pre-`Solution` solver interface, where each part writes its answer
+/// into a shared slot and only returns the time it took.
+pub trait LegacySolver: Send + Sync {
+    fn title(&self) -> &'static str;
+
+    fn part_a(&self, input: &str, out: Arc<Mutex<Answer>>) -> Result<Duration, BoxError>;
+
+    fn part_b(&self, input: &str, out: Arc<Mutex<Answer>>) -> Result<Duration, BoxError>;
+
+    fn is_empty(&self) -> bool;
+}
+
+/// Adapts a [`LegacySolver`] to [`Solver`].
+///
+/// Legacy parts parse for themselves, so the "parsed" input is just the raw
+/// text and the runner's timings include their parsing. They also never
+/// check for cancellation.
+pub struct Legacy<S>(pub S);
+
+impl<S: LegacySolver> Legacy<S> {
+    fn run(part: impl FnOnce(Arc<Mutex<Answer>>) -> Result<Duration, BoxError>)
+            -> Result<Answer, BoxError> {
+        let out = Arc::new(Mutex::new(Answer::Unimplemented));
+        part(out.clone())?;
+
+        let answer = std::mem::replace(&mut *out.lock().map_err(|e| e.to_string())?, Answer::Unimplemented);
+
+        Ok(answer)
+    }
+}
+
+impl<S: LegacySolver> Solver for Legacy<S> {
+    type Input<'a> = &'a str;
+
+    fn title(&self) -> &'static str {
+        self.0.title()
+    }
+
+    fn parse<'a>(&self, input: &'a str) -> Result<&'a str, BoxError> {
+        Ok(input)
+    }
+
+    fn part_a(&self, input: &&str, _ctx: &Context) -> Result<Answer, BoxError> {
+        Self::run(|out| self.0.part_a(input, out))
+    }
+
+    fn part_b(&self, input: &&str, _ctx: &Context) -> Result<Answer, BoxError> {
+        Self::run(|out| self.0.part_b(input, out))
+    }
+
+    fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{sync::{Arc, Mutex}, time::Duration};
+
+    use crate::*;
+    use super::{Legacy, LegacySolver};
+
+    struct Old;
+
+    impl LegacySolver for Old {
+        fn title(&self) -> &'static str {
+            r"Old Style"
+        }
+
+        fn part_a(&self, input: &str, out: Arc<Mutex<Answer>>) -> Result<Duration, BoxError> {
+            *out.lock().unwrap() = input.len().into();
+            Ok(Duration::from_millis(3))
+        }
+
+        fn part_b(&self, _input: &str, _out: Arc<Mutex<Answer>>) -> Result<Duration, BoxError> {
+            Err("Bad input!".into())
+        }
+
+        fn is_empty(&self) -> bool {
+            false
+        }
+    }
+
+    const TABLE: &[&dyn DynSolver] = &[&Legacy(Old)];
+
+    #[test]
+    fn adapts_answer() {
+        let parsed = TABLE[0].prepare("abcd").unwrap();
+        assert_eq!(parsed.part_a(&Context::default()).unwrap().answer, Answer::Number(4));
+        assert_eq!(TABLE[0].title(), "Old Style");
+    }
+
+    #[test]
+    fn adapts_error() {
+        let parsed = TABLE[0].prepare("abcd").unwrap();
+        assert!(parsed.part_b(&Context::default()).is_err());
+    }
+}
@@ -0,0 +1,116 @@
+use std::{
+    error::Error,
+    fmt::{self, Display},
+    sync::{atomic::{AtomicBool, Ordering}, Arc, Mutex},
+};
+
+/// Shared flag the runner flips to ask running parts to stop.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Returned by [`Context::check`] once the part has been cancelled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cancelled;
+
+impl Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "cancelled")
+    }
+}
+
+impl Error for Cancelled {}
+
+type ProgressSink = Box<dyn Fn(u64, u64) + Send + Sync>;
+
+/// What the runner hands a running part: a way to notice it should stop,
+/// somewhere to report progress, and somewhere to leave notes.
+///
+/// Long loops should call [`Context::check`] every so often and bail out
+/// with `?`; everything else is optional. Cancelling is best effort: a part
+/// that never checks runs to the end, and only an isolated run can kill it.
+#[derive(Default)]
+pub struct Context {
+    cancel: CancelToken,
+    progress: Option<ProgressSink>,
+    notes: Mutex<Vec<String>>,
+}
+
+impl Context {
+    pub fn new(cancel: CancelToken) -> Self {
+        Self { cancel, ..Self::default() }
+    }
+
+    /// Send [`Context::progress`] reports to `sink` as `(done, total)`.
+    pub fn with_progress(mut self, sink: impl Fn(u64, u64) + Send + Sync + 'static) -> Self {
+        self.progress = Some(Box::new(sink));
+        self
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel.is_cancelled()
+    }
+
+    /// `Err(Cancelled)` once the runner has asked this part to stop.
+    pub fn check(&self) -> Result<(), Cancelled> {
+        match self.is_cancelled() {
+            true => Err(Cancelled),
+            false => Ok(()),
+        }
+    }
+
+    /// Report that `done` out of `total` units of work are finished. The
+    /// units are up to the solver.
+    pub fn progress(&self, done: u64, total: u64) {
+        if let Some(sink) = &self.progress {
+            sink(done, total);
+        }
+    }
+
+    /// Leave a note that ends up in [`Solution::diagnostics`](crate::Solution).
+    pub fn note(&self, note: impl Into<String>) {
+        if let Ok(mut notes) = self.notes.lock() {
+            notes.push(note.into());
+        }
+    }
+
+    pub(crate) fn take_notes(&self) -> Vec<String> {
+        self.notes.lock().map(|mut n| std::mem::take(&mut *n)).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::{atomic::{AtomicU64, Ordering}, Arc};
+
+    use super::{CancelToken, Cancelled, Context};
+
+    #[test]
+    fn cancel() {
+        let token = CancelToken::default();
+        let ctx = Context::new(token.clone());
+
+        assert_eq!(ctx.check(), Ok(()));
+        token.cancel();
+        assert_eq!(ctx.check(), Err(Cancelled));
+    }
+
+    #[test]
+    fn progress() {
+        let seen = Arc::new(AtomicU64::new(0));
+        let sink = seen.clone();
+        let ctx = Context::default().with_progress(move |done, _| sink.store(done, Ordering::Relaxed));
+
+        ctx.progress(7, 10);
+        assert_eq!(seen.load(Ordering::Relaxed), 7);
+    }
+}
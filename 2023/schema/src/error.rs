@@ -0,0 +1,40 @@
+use std::{error::Error, fmt::{self, Display}};
+
+/// A malformed input, pinned to where in the file it went wrong.
+///
+/// Lines and columns count from 1, the way an editor shows them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SolveError {
+    pub line: usize,
+    pub column: usize,
+    /// The whole offending line.
+    pub snippet: String,
+    /// The text we couldn't make sense of; empty if the line ended early.
+    pub found: String,
+    /// What should have been there, e.g. "a number".
+    pub expected: String,
+}
+
+impl Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.found.as_str() {
+            "" => writeln!(f, "line {}, column {}: expected {}, found end of line",
+                           self.line, self.column, self.expected)?,
+            found => writeln!(f, "line {}, column {}: expected {}, found `{found}`",
+                              self.line, self.column, self.expected)?,
+        }
+
+        let gutter = self.line.to_string();
+        let pad = " ".repeat(gutter.len());
+        let lead: String = self.snippet.chars()
+                                       .take(self.column.saturating_sub(1))
+                                       .map(|c| if c == '\t' { '\t' } else { ' ' })
+                                       .collect();
+
+        writeln!(f, "{pad} |")?;
+        writeln!(f, "{gutter} | {}", self.snippet)?;
+        write!(f, "{pad} | {lead}{}", "^".repeat(self.found.chars().count().max(1)))
+    }
+}
+
+impl Error for SolveError {}
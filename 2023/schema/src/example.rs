@@ -0,0 +1,46 @@
+use crate::Answer;
+
+/// A worked example from the puzzle text, and the answers it gives.
+///
+/// Built in a `const` so a solver can hand out a `&'static` list of them:
+///
+/// ```
+/// # use schema::Example;
+/// const EXAMPLES: &[Example] = &[
+///     Example::new("small", "1\n2\n").part_a("3"),
+///     Example::new("large", "1\n2\n3\n").part_a("6").part_b("12"),
+/// ];
+/// # assert_eq!(EXAMPLES[1].expected_b(), Some(schema::Answer::Number(12)));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Example {
+    pub name: &'static str,
+    pub input: &'static str,
+    part_a: Option<&'static str>,
+    part_b: Option<&'static str>,
+}
+
+impl Example {
+    pub const fn new(name: &'static str, input: &'static str) -> Self {
+        Self { name, input, part_a: None, part_b: None }
+    }
+
+    /// Part A's answer for this input, written as the answers file would.
+    pub const fn part_a(mut self, answer: &'static str) -> Self {
+        self.part_a = Some(answer);
+        self
+    }
+
+    pub const fn part_b(mut self, answer: &'static str) -> Self {
+        self.part_b = Some(answer);
+        self
+    }
+
+    pub fn expected_a(&self) -> Option<Answer> {
+        self.part_a.map(|a| a.parse().unwrap_or_else(|e| match e {}))
+    }
+
+    pub fn expected_b(&self) -> Option<Answer> {
+        self.part_b.map(|b| b.parse().unwrap_or_else(|e| match e {}))
+    }
+}
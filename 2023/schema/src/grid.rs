@@ -0,0 +1,261 @@
+//! Pixel grid answers, for the puzzles that spell their answer out in
+//! capital letters.
+
+use std::{fmt::{self, Display}, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+/// A 2D bitmap, stored row by row.
+///
+/// Serialized as its `#`/`.` picture.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(into = "String", try_from = "String")]
+pub struct Bitmap {
+    width: usize,
+    height: usize,
+    pixels: Vec<bool>,
+}
+
+impl Bitmap {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self { width, height, pixels: vec![false; width * height] }
+    }
+
+    /// Build a bitmap from rows of pixels; short rows are padded with off
+    /// pixels.
+    pub fn from_rows<R: AsRef<[bool]>>(rows: impl IntoIterator<Item = R>) -> Self {
+        let rows: Vec<R> = rows.into_iter().collect();
+        let width = rows.iter().map(|r| r.as_ref().len()).max().unwrap_or(0);
+
+        let mut map = Self::new(width, rows.len());
+
+        for (y, row) in rows.iter().enumerate() {
+            for (x, &on) in row.as_ref().iter().enumerate() {
+                map.set(x, y, on);
+            }
+        }
+
+        map
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> bool {
+        x < self.width && y < self.height && self.pixels[y * self.width + x]
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, on: bool) {
+        assert!(x < self.width && y < self.height, "({x}, {y}) is outside the bitmap");
+        self.pixels[y * self.width + x] = on;
+    }
+
+    fn column_is_blank(&self, x: usize) -> bool {
+        (0..self.height).all(|y| !self.get(x, y))
+    }
+
+    fn row_is_blank(&self, y: usize) -> bool {
+        (0..self.width).all(|x| !self.get(x, y))
+    }
+
+    /// Copy out the `width` x `height` block starting at (`x`, `y`).
+    fn crop(&self, x: usize, y: usize, width: usize, height: usize) -> Self {
+        let mut out = Self::new(width, height);
+
+        for dy in 0..height {
+            for dx in 0..width {
+                out.set(dx, dy, self.get(x + dx, y + dy));
+            }
+        }
+
+        out
+    }
+
+    /// Split into glyphs on the blank columns, after dropping any blank rows
+    /// above and below the text.
+    fn glyphs(&self) -> Vec<Self> {
+        let Some(top) = (0..self.height).find(|&y| !self.row_is_blank(y)) else {
+            return Vec::new();
+        };
+        let bottom = (0..self.height).rfind(|&y| !self.row_is_blank(y)).unwrap_or(top);
+        let height = bottom - top + 1;
+
+        let mut glyphs = Vec::new();
+        let mut start = None;
+
+        for x in 0..=self.width {
+            match (start, x == self.width || self.column_is_blank(x)) {
+                (None, false) => start = Some(x),
+                (Some(s), true) => {
+                    glyphs.push(self.crop(s, top, x - s, height));
+                    start = None;
+                },
+                _ => (),
+            }
+        }
+
+        glyphs
+    }
+
+    /// Read the bitmap as text written in one of the Advent of Code fonts
+    /// (4x6 or 6x10 letters).
+    ///
+    /// Returns `None` if any glyph isn't a letter we know.
+    pub fn ocr(&self) -> Option<String> {
+        let glyphs = self.glyphs();
+
+        let font = match glyphs.first()?.height {
+            6 => font(FONT_6),
+            10 => font(FONT_10),
+            _ => return None,
+        };
+
+        glyphs.iter()
+              .map(|g| font.iter().find(|(_, f)| f == g).map(|&(c, _)| c))
+              .collect()
+    }
+}
+
+impl Display for Bitmap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for y in 0..self.height {
+            if y > 0 {
+                writeln!(f)?;
+            }
+
+            for x in 0..self.width {
+                write!(f, "{}", if self.get(x, y) { '█' } else { ' ' })?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Parses the usual puzzle rendering: `#` is on, `.` or a space is off.
+impl FromStr for Bitmap {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.lines()
+         .map(|l| l.chars().map(|c| match c {
+                '#' | '█' => Ok(true),
+                '.' | ' ' => Ok(false),
+                _ => Err(format!("Unexpected pixel {c:?}")),
+            }).collect::<Result<Vec<bool>, _>>())
+         .collect::<Result<Vec<_>, _>>()
+         .map(Self::from_rows)
+    }
+}
+
+impl From<Bitmap> for String {
+    fn from(map: Bitmap) -> Self {
+        (0..map.height)
+            .map(|y| (0..map.width).map(|x| if map.get(x, y) { '#' } else { '.' }).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+impl TryFrom<String> for Bitmap {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+/// A font is written out as its letters side by side and split up the same
+/// way a puzzle's bitmap is.
+struct Font {
+    letters: &'static str,
+    rows: &'static [&'static str],
+}
+
+fn font(f: Font) -> Vec<(char, Bitmap)> {
+    let map: Bitmap = f.rows.join("\n").parse().expect("Bad font");
+
+    f.letters.chars().zip(map.glyphs()).collect()
+}
+
+const FONT_6: Font = Font {
+    letters: "ABCEFGHIJKLOPRSUYZ",
+    rows: &[
+        ".##..###...##..####.####..##..#..#.###...##.#..#.#.....##..###..###...###.#..#.#...#.####",
+        "#..#.#..#.#..#.#....#....#..#.#..#..#.....#.#.#..#....#..#.#..#.#..#.#....#..#.#...#....#",
+        "#..#.###..#....###..###..#....####..#.....#.##...#....#..#.#..#.#..#.#....#..#..#.#....#.",
+        "####.#..#.#....#....#....#.##.#..#..#.....#.#.#..#....#..#.###..###...##..#..#...#....#..",
+        "#..#.#..#.#..#.#....#....#..#.#..#..#..#..#.#.#..#....#..#.#....#.#.....#.#..#...#...#...",
+        "#..#.###...##..####.#.....###.#..#.###..##..#..#.####..##..#....#..#.###...##....#...####",
+    ],
+};
+
+const FONT_10: Font = Font {
+    letters: "ABCEFGHJKLNPRXZ",
+    rows: &[
+        "..##....#####....####...######..######...####...#....#.....###..#....#..#.......#....#..#####...#####...#....#..######",
+        ".#..#...#....#..#....#..#.......#.......#....#..#....#......#...#...#...#.......##...#..#....#..#....#..#....#.......#",
+        "#....#..#....#..#.......#.......#.......#.......#....#......#...#..#....#.......##...#..#....#..#....#...#..#........#",
+        "#....#..#....#..#.......#.......#.......#.......#....#......#...#.#.....#.......#.#..#..#....#..#....#...#..#.......#.",
+        "#....#..#####...#.......#####...#####...#.......######......#...##......#.......#.#..#..#####...#####.....##.......#..",
+        "######..#....#..#.......#.......#.......#..###..#....#......#...##......#.......#..#.#..#.......#..#......##......#...",
+        "#....#..#....#..#.......#.......#.......#....#..#....#......#...#.#.....#.......#..#.#..#.......#...#....#..#....#....",
+        "#....#..#....#..#.......#.......#.......#....#..#....#..#...#...#..#....#.......#...##..#.......#...#....#..#...#.....",
+        "#....#..#....#..#....#..#.......#.......#...##..#....#..#...#...#...#...#.......#...##..#.......#....#..#....#..#.....",
+        "#....#..#####....####...######..#........###.#..#....#...###....#....#..######..#....#..#.......#....#..#....#..######",
+    ],
+};
+
+#[cfg(test)]
+mod test {
+    use indoc::indoc;
+
+    use super::Bitmap;
+
+    #[test]
+    fn small_font() {
+        let map: Bitmap = indoc! {"
+            ####.#..#.###..####.###....##..##..#....
+            #....#..#.#..#....#.#..#....#.#..#.#....
+            ###..####.#..#...#..#..#....#.#....#....
+            #....#..#.###...#...###.....#.#.##.#....
+            #....#..#.#....#....#....#..#.#..#.#....
+            ####.#..#.#....####.#.....##...###.####.
+        "}.parse().unwrap();
+
+        assert_eq!(map.ocr().as_deref(), Some("EHPZPJGL"));
+    }
+
+    #[test]
+    fn large_font() {
+        let map: Bitmap = indoc! {"
+            .......................
+            .#....#..######.....###.
+            .#....#..#...........#..
+            .#....#..#...........#..
+            .#....#..#...........#..
+            .######..#####.......#..
+            .#....#..#...........#..
+            .#....#..#...........#..
+            .#....#..#.......#...#..
+            .#....#..#.......#...#..
+            .#....#..#........###...
+            .......................
+        "}.parse().unwrap();
+
+        assert_eq!(map.ocr().as_deref(), Some("HFJ"));
+    }
+
+    #[test]
+    fn unknown() {
+        let map: Bitmap = "#.#\n.#.\n#.#".parse().unwrap();
+
+        assert_eq!(map.ocr(), None);
+        assert_eq!(map.to_string(), "█ █\n █ \n█ █");
+    }
+}
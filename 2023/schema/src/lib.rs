@@ -1,5 +1,23 @@
 mod solver;
 mod answer;
+mod context;
+mod error;
+mod example;
+mod grid;
+mod year;
+pub mod alloc;
+pub mod compat;
+pub mod parse;
+pub mod registry;
 
-pub use solver::Solver;
+pub use solver::{BoxError, DynSolver, EmptySolver, Parsed, Solution, Solver};
 pub use answer::Answer;
+pub use context::{CancelToken, Cancelled, Context};
+pub use error::SolveError;
+pub use example::Example;
+pub use grid::Bitmap;
+pub use year::{Year, FIRST_YEAR, LAST_DAY};
+pub use schema_macros::solver;
+
+#[doc(hidden)]
+pub use linkme;
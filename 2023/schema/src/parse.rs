@@ -0,0 +1,158 @@
+//! Small helpers for turning puzzle input into [`SolveError`]s rather than
+//! panics.
+//!
+//! ```
+//! # use schema::{parse, SolveError};
+//! fn sizes(input: &str) -> Result<Vec<u32>, SolveError> {
+//!     parse::lines(input)
+//!         .map(|line| {
+//!             let (_, size) = line.split_once(": ")?;
+//!             line.parse(size, "a size")
+//!         })
+//!         .collect()
+//! }
+//!
+//! assert_eq!(sizes("a: 1\nb: 2").unwrap(), vec![1, 2]);
+//! assert_eq!(sizes("a: 1\nb: two").unwrap_err().line, 2);
+//! ```
+
+use std::fmt::Display;
+use std::str::FromStr;
+
+use crate::SolveError;
+
+/// One line of the input, with its 1-based line number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Line<'a> {
+    pub number: usize,
+    pub text: &'a str,
+}
+
+/// Iterate over the lines of `input`, keeping track of where each one is.
+pub fn lines(input: &str) -> impl Iterator<Item = Line<'_>> + Clone {
+    input.lines()
+         .enumerate()
+         .map(|(i, text)| Line { number: i + 1, text })
+}
+
+impl<'a> Line<'a> {
+    /// An error for the character at `column` (0-based, in chars).
+    pub fn error_at(&self, column: usize, expected: impl Display) -> SolveError {
+        let found = self.text.chars().nth(column).map(String::from).unwrap_or_default();
+
+        SolveError {
+            line: self.number,
+            column: column + 1,
+            snippet: self.text.to_string(),
+            found,
+            expected: expected.to_string(),
+        }
+    }
+
+    /// An error for `token`, which must be a slice of this line.
+    pub fn error_on(&self, token: &str, expected: impl Display) -> SolveError {
+        let offset = (token.as_ptr() as usize).saturating_sub(self.text.as_ptr() as usize);
+
+        // Counted this way, an offset inside a character can't panic.
+        let column = self.text.char_indices().take_while(|&(i, _)| i < offset).count();
+
+        SolveError {
+            line: self.number,
+            column: column + 1,
+            snippet: self.text.to_string(),
+            found: token.to_string(),
+            expected: expected.to_string(),
+        }
+    }
+
+    /// An error pointing just past the end of the line.
+    pub fn error_eol(&self, expected: impl Display) -> SolveError {
+        self.error_on(&self.text[self.text.len()..], expected)
+    }
+
+    /// Like [`str::split_once`], but a missing delimiter is an error.
+    pub fn split_once(&self, delim: &str) -> Result<(&'a str, &'a str), SolveError> {
+        self.text.split_once(delim)
+                 .ok_or_else(|| self.error_eol(format_args!("`{delim}`")))
+    }
+
+    /// Parse `token`, a slice of this line, reporting `expected` on failure.
+    pub fn parse<T: FromStr>(&self, token: &str, expected: impl Display) -> Result<T, SolveError> {
+        token.trim()
+             .parse()
+             .map_err(|_| self.error_on(token.trim(), expected))
+    }
+
+    /// Take the next token from `tokens`, a split of this line.
+    pub fn next(&self, tokens: &mut impl Iterator<Item = &'a str>, expected: impl Display)
+            -> Result<&'a str, SolveError> {
+        tokens.next().ok_or_else(|| self.error_eol(expected))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use indoc::indoc;
+
+    use super::{lines, Line};
+    use crate::SolveError;
+
+    const INPUT: &str = indoc! {"
+        red 3
+        blue seven
+    "};
+
+    #[test]
+    fn points_at_token() {
+        let line = lines(INPUT).nth(1).unwrap();
+        let (_, n) = line.text.split_once(' ').unwrap();
+
+        let err = line.parse::<u32>(n, "a number").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 6));
+        assert_eq!(err.to_string(), indoc! {"
+            line 2, column 6: expected a number, found `seven`
+              |
+            2 | blue seven
+              |      ^^^^^"});
+    }
+
+    #[test]
+    fn missing_delimiter() {
+        let line = lines(INPUT).next().unwrap();
+
+        let err = line.split_once(":").unwrap_err();
+        assert_eq!((err.line, err.column), (1, 6));
+        assert!(err.to_string().starts_with("line 1, column 6: expected `:`, found end of line"));
+    }
+
+    #[test]
+    fn multi_byte() {
+        let line = Line { number: 1, text: "é → 7x" };
+        let (_, n) = line.text.rsplit_once(' ').unwrap();
+
+        let err = line.parse::<u32>(n, "a number").unwrap_err();
+        assert_eq!((err.column, err.found.as_str()), (5, "7x"));
+
+        let err = line.error_on(&line.text[3..], "a number");
+        assert_eq!(err.column, 3);
+        assert!(err.to_string().ends_with("1 | é → 7x\n  |   ^^^^"), "{err}");
+
+        // Not a slice of the line, so it could point anywhere, even inside
+        // a character.
+        let elsewhere = String::from("7x");
+        assert!(line.error_on(&elsewhere, "a number").column <= line.text.chars().count() + 1);
+    }
+
+    #[test]
+    fn column_zero() {
+        let err = SolveError {
+            line: 1,
+            column: 0,
+            snippet: "abc".to_string(),
+            found: "a".to_string(),
+            expected: "a digit".to_string(),
+        };
+
+        assert!(err.to_string().ends_with("1 | abc\n  | ^"), "{err}");
+    }
+}
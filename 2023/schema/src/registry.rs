@@ -0,0 +1,107 @@
+//! Every solver marked with [`#[solver]`](crate::solver), looked up by
+//! year and day.
+
+use std::{collections::BTreeMap, error::Error, fmt::{self, Display}};
+
+use linkme::distributed_slice;
+
+use crate::{DynSolver, Year};
+
+/// One `#[solver(year = .., day = ..)]` entry.
+pub struct Registration {
+    pub year: u16,
+    pub day: u8,
+    pub solver: &'static dyn DynSolver,
+}
+
+/// Filled in at link time by `#[solver]`, in no particular order.
+#[distributed_slice]
+pub static SOLVERS: [Registration];
+
+/// Two solvers claimed the same puzzle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Duplicate {
+    pub year: u16,
+    pub day: u8,
+}
+
+impl Display for Duplicate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "more than one solver registered for {} day {}", self.year, self.day)
+    }
+}
+
+impl Error for Duplicate {}
+
+/// The registered solvers, grouped into [`Year`]s.
+pub struct Registry {
+    years: BTreeMap<u16, Year>,
+}
+
+impl Registry {
+    /// Collect everything in [`SOLVERS`].
+    pub fn load() -> Result<Self, Duplicate> {
+        Self::from_entries(SOLVERS.iter())
+    }
+
+    pub fn from_entries<'a>(entries: impl IntoIterator<Item = &'a Registration>) -> Result<Self, Duplicate> {
+        let mut years = BTreeMap::new();
+
+        for r in entries {
+            let year = years.entry(r.year).or_insert_with(|| Year::new(r.year));
+
+            if year.insert(r.day, r.solver).is_some() {
+                return Err(Duplicate { year: r.year, day: r.day });
+            }
+        }
+
+        Ok(Self { years })
+    }
+
+    pub fn get(&self, year: u16, day: u8) -> Option<&'static dyn DynSolver> {
+        self.year(year)?.get(day)
+    }
+
+    pub fn year(&self, year: u16) -> Option<&Year> {
+        self.years.get(&year)
+    }
+
+    /// Every year with at least one solver, in order.
+    pub fn years(&self) -> impl Iterator<Item = &Year> {
+        self.years.values()
+    }
+
+    /// The most recent year, which the runner defaults to.
+    pub fn latest(&self) -> Option<&Year> {
+        self.years.values().next_back()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Duplicate, Registration, Registry};
+    use crate::EmptySolver;
+
+    const fn entry(year: u16, day: u8) -> Registration {
+        Registration { year, day, solver: &EmptySolver }
+    }
+
+    #[test]
+    fn lookup() {
+        let entries = [entry(2023, 3), entry(2022, 25), entry(2023, 1)];
+        let reg = Registry::from_entries(&entries).unwrap();
+
+        assert!(reg.get(2023, 1).is_some());
+        assert!(reg.get(2023, 2).is_none());
+        assert!(reg.year(2023).unwrap().days().map(|(d, _)| d).eq([1, 3]));
+        assert!(reg.years().map(|y| y.number()).eq([2022, 2023]));
+        assert_eq!(reg.latest().map(|y| y.number()), Some(2023));
+    }
+
+    #[test]
+    fn duplicate() {
+        let entries = [entry(2023, 1), entry(2023, 1)];
+
+        assert_eq!(Registry::from_entries(&entries).err(), Some(Duplicate { year: 2023, day: 1 }));
+    }
+}
@@ -1,37 +1,186 @@
-use std::{sync::{ Arc, Mutex, }, time::Duration};
+use std::{error::Error, time::{Duration, Instant}};
 
-use crate::Answer;
+use crate::{alloc::{self, Allocations}, Answer, Context, Example};
 
+/// The error type solvers bubble up to the runner.
+pub type BoxError = Box<dyn Error + Send + Sync>;
+
+/// Everything a single part hands back to the runner.
+#[derive(Debug, PartialEq)]
+pub struct Solution {
+    pub answer: Answer,
+    pub elapsed: Duration,
+    pub diagnostics: Vec<String>,
+    /// What the part allocated, when the binary counts allocations.
+    pub allocations: Option<Allocations>,
+}
+
+impl Solution {
+    pub fn new(answer: impl Into<Answer>, elapsed: Duration) -> Self {
+        Self { answer: answer.into(), elapsed, diagnostics: Vec::new(), allocations: None }
+    }
+
+    /// Attach a free-form note that the runner prints under the answer.
+    pub fn with_diagnostic(mut self, note: impl Into<String>) -> Self {
+        self.diagnostics.push(note.into());
+        self
+    }
+}
+
+/// A single day's puzzle.
+///
+/// The raw input is turned into `Input` once by [`Solver::parse`] and the
+/// result is shared by both parts, which may run concurrently. Each part
+/// gets its own [`Context`] to watch for cancellation through.
 pub trait Solver: Send + Sync {
+    type Input<'a>: Sync;
+
+    fn title(&self) -> &'static str;
+
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Input<'a>, BoxError>;
+
+    fn part_a(&self, input: &Self::Input<'_>, ctx: &Context) -> Result<Answer, BoxError>;
+
+    fn part_b(&self, input: &Self::Input<'_>, ctx: &Context) -> Result<Answer, BoxError>;
+
+    fn is_empty(&self) -> bool {
+        false
+    }
+
+    /// Which of `(part_a, part_b)` are written. Override this for a part
+    /// that still just returns [`Answer::Unimplemented`], so tooling can tell
+    /// without running it.
+    fn implemented(&self) -> (bool, bool) {
+        (true, true)
+    }
+
+    /// The worked examples from the puzzle text, so the runner can check a
+    /// day against them without its tests.
+    fn examples(&self) -> &'static [Example] {
+        &[]
+    }
+}
+
+/// Object safe view of a [`Solver`], so days with different `Input` types
+/// can live in the same table.
+///
+/// Implemented for every [`Solver`]; the runner only ever talks to this.
+pub trait DynSolver: Send + Sync {
     fn title(&self) -> &'static str;
 
-    fn part_a(&self, input: &str, out: Arc<Mutex<Answer>>) 
-        -> Result<Duration, Box<(dyn std::error::Error + Send + Sync)>>;
+    /// Parse the raw input, timing how long it took.
+    fn prepare<'a>(&'a self, input: &'a str) -> Result<Parsed<'a>, BoxError>;
 
-    fn part_b(&self, input: &str, out: Arc<Mutex<Answer>>) 
-        -> Result<Duration, Box<(dyn std::error::Error + Send + Sync)>>;
-    
     fn is_empty(&self) -> bool;
+
+    fn implemented(&self) -> (bool, bool);
+
+    fn examples(&self) -> &'static [Example];
+}
+
+impl<S: Solver> DynSolver for S {
+    fn title(&self) -> &'static str {
+        Solver::title(self)
+    }
+
+    fn prepare<'a>(&'a self, input: &'a str) -> Result<Parsed<'a>, BoxError> {
+        let ts = Instant::now();
+        let input = self.parse(input)?;
+        let elapsed = ts.elapsed();
+
+        Ok(Parsed { elapsed, parts: Box::new(Bound { solver: self, input }) })
+    }
+
+    fn is_empty(&self) -> bool {
+        Solver::is_empty(self)
+    }
+
+    fn implemented(&self) -> (bool, bool) {
+        Solver::implemented(self)
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        Solver::examples(self)
+    }
+}
+
+/// A parsed input, ready to have either part run against it.
+pub struct Parsed<'a> {
+    /// Time spent in [`Solver::parse`].
+    pub elapsed: Duration,
+    parts: Box<dyn Parts + 'a>,
+}
+
+impl Parsed<'_> {
+    pub fn part_a(&self, ctx: &Context) -> Result<Solution, BoxError> {
+        Self::timed(ctx, || self.parts.part_a(ctx))
+    }
+
+    pub fn part_b(&self, ctx: &Context) -> Result<Solution, BoxError> {
+        Self::timed(ctx, || self.parts.part_b(ctx))
+    }
+
+    fn timed(ctx: &Context, part: impl FnOnce() -> Result<Answer, BoxError>) -> Result<Solution, BoxError> {
+        let ts = Instant::now();
+        let (answer, allocations) = alloc::measure(part);
+        let elapsed = ts.elapsed();
+
+        let mut solution = Solution::new(answer?, elapsed);
+        solution.diagnostics = ctx.take_notes();
+        solution.allocations = allocations;
+
+        Ok(solution)
+    }
+}
+
+/// Type erased pairing of a solver with its parsed input.
+trait Parts: Sync {
+    fn part_a(&self, ctx: &Context) -> Result<Answer, BoxError>;
+
+    fn part_b(&self, ctx: &Context) -> Result<Answer, BoxError>;
+}
+
+struct Bound<'a, S: Solver> {
+    solver: &'a S,
+    input: S::Input<'a>,
+}
+
+impl<S: Solver> Parts for Bound<'_, S> {
+    fn part_a(&self, ctx: &Context) -> Result<Answer, BoxError> {
+        self.solver.part_a(&self.input, ctx)
+    }
+
+    fn part_b(&self, ctx: &Context) -> Result<Answer, BoxError> {
+        self.solver.part_b(&self.input, ctx)
+    }
 }
 
 pub struct EmptySolver;
 
 impl Solver for EmptySolver {
+    type Input<'a> = ();
+
     fn title(&self) -> &'static str {
         r""
     }
 
-    fn part_a(&self, _input: &str, _out: Arc<Mutex<Answer>>) 
-            -> Result<Duration, Box<(dyn std::error::Error + Send + Sync)>> {
-        Ok(Duration::new(0, 0))
+    fn parse(&self, _input: &str) -> Result<(), BoxError> {
+        Ok(())
     }
 
-    fn part_b(&self, _input: &str, _out: Arc<Mutex<Answer>>) 
-            -> Result<Duration, Box<(dyn std::error::Error + Send + Sync)>> {
-        Ok(Duration::new(0, 0))
+    fn part_a(&self, _input: &(), _ctx: &Context) -> Result<Answer, BoxError> {
+        Ok(Answer::Unimplemented)
+    }
+
+    fn part_b(&self, _input: &(), _ctx: &Context) -> Result<Answer, BoxError> {
+        Ok(Answer::Unimplemented)
     }
 
     fn is_empty(&self) -> bool {
         true
     }
+
+    fn implemented(&self) -> (bool, bool) {
+        (false, false)
+    }
 }
@@ -0,0 +1,74 @@
+use std::{collections::BTreeMap, path::{Path, PathBuf}};
+
+use crate::DynSolver;
+
+/// The first year there was an event; `--year` won't take anything earlier.
+pub const FIRST_YEAR: u16 = 2015;
+
+/// The last puzzle day of an event.
+pub const LAST_DAY: u8 = 25;
+
+/// The solvers registered for one year's event, by day.
+///
+/// A year crate needs nothing beyond `#[solver(year = .., day = ..)]` on its
+/// days and being linked into the runner; the [`Registry`](crate::registry::Registry)
+/// groups them into `Year`s.
+pub struct Year {
+    year: u16,
+    days: BTreeMap<u8, &'static dyn DynSolver>,
+}
+
+impl Year {
+    pub fn new(year: u16) -> Self {
+        Self { year, days: BTreeMap::new() }
+    }
+
+    pub fn number(&self) -> u16 {
+        self.year
+    }
+
+    /// Add a day, handing back the solver it replaced if there was one.
+    pub fn insert(&mut self, day: u8, solver: &'static dyn DynSolver) -> Option<&'static dyn DynSolver> {
+        self.days.insert(day, solver)
+    }
+
+    pub fn get(&self, day: u8) -> Option<&'static dyn DynSolver> {
+        self.days.get(&day).copied()
+    }
+
+    /// The registered days, in order.
+    pub fn days(&self) -> impl Iterator<Item = (u8, &'static dyn DynSolver)> + '_ {
+        self.days.iter().map(|(&d, &s)| (d, s))
+    }
+
+    /// Days of the event with no solver yet.
+    pub fn missing(&self) -> impl Iterator<Item = u8> + '_ {
+        (1..=LAST_DAY).filter(|d| !self.days.contains_key(d))
+    }
+
+    /// Where a day's puzzle input lives under `root`: `<root>/<year>/day<NN>`,
+    /// with the day zero padded so they sort.
+    pub fn input_path(&self, root: &Path, day: u8) -> PathBuf {
+        root.join(self.year.to_string()).join(format!("day{day:02}"))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::Path;
+
+    use super::Year;
+    use crate::EmptySolver;
+
+    #[test]
+    fn gaps() {
+        let mut year = Year::new(2015);
+        year.insert(2, &EmptySolver);
+        year.insert(1, &EmptySolver);
+
+        assert!(year.days().map(|(d, _)| d).eq([1, 2]));
+        assert_eq!(year.missing().next(), Some(3));
+        assert_eq!(year.missing().count(), 23);
+        assert_eq!(year.input_path(Path::new("input"), 7), Path::new("input/2015/day07"));
+    }
+}
@@ -0,0 +1,221 @@
+//! Known-good answers for our own inputs, kept in `answers/<year>.toml`:
+//!
+//! ```toml
+//! [day1]
+//! a = "142"
+//! b = 281
+//! ```
+
+use std::{collections::BTreeMap, fmt::Write as _, path::{Path, PathBuf}};
+
+use schema::{Answer, BoxError};
+use serde::{Deserialize, Serialize};
+
+use crate::runner::Part;
+
+/// Answers are usually written as strings, but a bare TOML number is fine
+/// too.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+enum Stored {
+    Int(i64),
+    Float(f64),
+    Text(String),
+}
+
+impl From<&Stored> for Answer {
+    fn from(s: &Stored) -> Self {
+        match s {
+            Stored::Int(n) => Answer::from(*n).normalized(),
+            Stored::Float(f) => Answer::Float(*f),
+            Stored::Text(t) => t.parse().unwrap_or_else(|e| match e {}),
+        }
+    }
+}
+
+/// One day's answers: a `[day<N>]` table, or a whole file next to a
+/// [batch](crate::batch) input.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DayAnswers {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    a: Option<Stored>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    b: Option<Stored>,
+}
+
+impl DayAnswers {
+    /// Read a file of just `a = ..` and `b = ..`; `None` if there isn't one.
+    pub fn load(path: &Path) -> Result<Option<Self>, BoxError> {
+        match std::fs::read_to_string(path) {
+            Ok(text) => toml::from_str(&text).map(Some).map_err(|e| format!("{}: {e}", path.display()).into()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(format!("{}: {e}", path.display()).into()),
+        }
+    }
+
+    pub fn get(&self, part: Part) -> Option<Answer> {
+        match part {
+            Part::A => self.a.as_ref(),
+            Part::B => self.b.as_ref(),
+        }.map(Answer::from)
+    }
+}
+
+/// One year's answers file.
+#[derive(Debug, Default)]
+pub struct AnswerStore {
+    days: BTreeMap<String, DayAnswers>,
+}
+
+impl AnswerStore {
+    /// Where answers live unless told otherwise.
+    pub const DEFAULT_ROOT: &'static str = "./answers";
+
+    pub fn path(root: &Path, year: u16) -> PathBuf {
+        root.join(format!("{year}.toml"))
+    }
+
+    /// Read `<root>/<year>.toml`; a missing file is just an empty store.
+    pub fn load(root: &Path, year: u16) -> Result<Self, BoxError> {
+        let path = Self::path(root, year);
+
+        match std::fs::read_to_string(&path) {
+            Ok(text) => Self::from_toml(&text).map_err(|e| format!("{}: {e}", path.display()).into()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(format!("{}: {e}", path.display()).into()),
+        }
+    }
+
+    fn from_toml(text: &str) -> Result<Self, toml::de::Error> {
+        Ok(Self { days: toml::from_str(text)? })
+    }
+
+    pub fn get(&self, day: u8, part: Part) -> Option<Answer> {
+        self.days.get(&format!("day{day}"))?.get(part)
+    }
+
+    /// Remember `answer` for `day`'s `part`, replacing any earlier one.
+    ///
+    /// It's kept as the text of its [normalized](Answer::normalized) form,
+    /// which reads back as the same answer: a grid OCR can't read is kept as
+    /// its `#`/`.` picture.
+    ///
+    /// Returns `false`, storing nothing, for [`Answer::Unimplemented`].
+    pub fn set(&mut self, day: u8, part: Part, answer: &Answer) -> bool {
+        if matches!(answer, Answer::Unimplemented) {
+            return false;
+        }
+
+        let entry = self.days.entry(format!("day{day}")).or_default();
+        let stored = Some(Stored::Text(answer.normalized().to_string()));
+
+        match part {
+            Part::A => entry.a = stored,
+            Part::B => entry.b = stored,
+        }
+
+        true
+    }
+
+    /// Write `<root>/<year>.toml`, creating `root` if needed.
+    pub fn save(&self, root: &Path, year: u16) -> Result<(), BoxError> {
+        std::fs::create_dir_all(root)?;
+        std::fs::write(Self::path(root, year), self.to_toml()?)?;
+
+        Ok(())
+    }
+
+    /// Days in numeric order, which a plain map of `dayN` keys wouldn't give.
+    fn to_toml(&self) -> Result<String, BoxError> {
+        let mut days: Vec<(u8, &String, &DayAnswers)> = self.days.iter()
+            .map(|(k, v)| (k.trim_start_matches("day").parse().unwrap_or(u8::MAX), k, v))
+            .collect();
+        days.sort_by_key(|&(n, k, _)| (n, k));
+
+        let mut out = String::new();
+
+        for (i, (_, key, answers)) in days.into_iter().enumerate() {
+            if i > 0 {
+                out.push('\n');
+            }
+
+            write!(out, "[{key}]\n{}", toml::to_string(answers)?)?;
+        }
+
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use indoc::indoc;
+    use schema::Answer;
+
+    use super::AnswerStore;
+    use crate::runner::Part;
+
+    #[test]
+    fn reads_mixed() {
+        let store = AnswerStore::from_toml(indoc! {r#"
+            [day1]
+            a = "142"
+            b = 281
+
+            [day3]
+            b = "HJKL"
+        "#}).unwrap();
+
+        assert_eq!(store.get(1, Part::A), Some(Answer::Number(142)));
+        assert_eq!(store.get(1, Part::B), Some(Answer::Number(281)));
+        assert_eq!(store.get(3, Part::A), None);
+        assert_eq!(store.get(3, Part::B), Some(Answer::from("HJKL")));
+        assert_eq!(store.get(2, Part::A), None);
+    }
+
+    #[test]
+    fn writes_in_day_order() {
+        let mut store = AnswerStore::default();
+
+        assert!(store.set(10, Part::A, &Answer::Number(7)));
+        assert!(store.set(2, Part::B, &Answer::from("HJKL")));
+        assert!(store.set(2, Part::A, &Answer::Signed(-4)));
+        assert!(!store.set(3, Part::A, &Answer::Unimplemented));
+
+        let text = store.to_toml().unwrap();
+
+        assert_eq!(text, indoc! {r#"
+            [day2]
+            a = "-4"
+            b = "HJKL"
+
+            [day10]
+            a = "7"
+        "#});
+
+        let back = AnswerStore::from_toml(&text).unwrap();
+        assert_eq!(back.get(2, Part::A), Some(Answer::Signed(-4)));
+        assert_eq!(back.get(10, Part::A), Some(Answer::Number(7)));
+    }
+
+    #[test]
+    fn reads_back_what_it_writes() {
+        let answers = [
+            Answer::Grid("#.#\n.#.".parse().unwrap()),
+            Answer::Grid("##.#".parse().unwrap()),
+            Answer::Float(3.0),
+            Answer::from("HJKL"),
+        ];
+
+        let mut store = AnswerStore::default();
+
+        for (day, a) in (1..).zip(&answers) {
+            assert!(store.set(day, Part::A, a));
+        }
+
+        let back = AnswerStore::from_toml(&store.to_toml().unwrap()).unwrap();
+
+        for (day, a) in (1..).zip(&answers) {
+            assert_eq!(back.get(day, Part::A).as_ref(), Some(a), "day {day}");
+        }
+    }
+}
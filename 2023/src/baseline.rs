@@ -0,0 +1,192 @@
+//! Saved benchmark results, to compare later runs against.
+
+use std::{
+    fmt::{self, Write as _},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use schema::BoxError;
+use serde::{Deserialize, Serialize};
+
+use crate::bench::{DayBench, Stats};
+
+/// One phase of one day, on one input.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Record {
+    year: u16,
+    day: u8,
+    phase: String,
+    input: String,
+    runs: usize,
+    min_ns: u64,
+    median_ns: u64,
+    mean_ns: u64,
+    p95_ns: u64,
+    stddev_ns: u64,
+}
+
+impl Record {
+    fn new(year: u16, day: u8, phase: String, input: String, s: &Stats) -> Self {
+        let ns = |d: Duration| d.as_nanos() as u64;
+
+        Self {
+            year, day, phase, input,
+            runs: s.runs,
+            min_ns: ns(s.min),
+            median_ns: ns(s.median),
+            mean_ns: ns(s.mean),
+            p95_ns: ns(s.p95),
+            stddev_ns: ns(s.stddev),
+        }
+    }
+
+    fn same_key(&self, other: &Record) -> bool {
+        (self.year, self.day, &self.phase, &self.input) == (other.year, other.day, &other.phase, &other.input)
+    }
+}
+
+/// A results file, by default `results/bench.json`.
+pub struct Baseline {
+    path: PathBuf,
+    records: Vec<Record>,
+}
+
+/// How one phase did against its baseline.
+pub struct Comparison {
+    pub day: u8,
+    pub phase: String,
+    /// Baseline median, if there's one for this input.
+    pub before: Option<Duration>,
+    pub now: Duration,
+    pub regressed: bool,
+}
+
+impl Comparison {
+    /// Relative change in median, e.g. `0.25` for 25% slower.
+    pub fn change(&self) -> Option<f64> {
+        self.before.map(|b| self.now.as_secs_f64() / b.as_secs_f64().max(f64::EPSILON) - 1.0)
+    }
+}
+
+/// Every measured phase in `benches`, as `(day, phase, input, stats)`.
+fn phases(benches: &[DayBench]) -> impl Iterator<Item = (u8, String, &str, &Stats)> {
+    benches.iter().flat_map(|b| {
+        let input = b.input_hash.as_deref().unwrap_or_default();
+
+        std::iter::once(("Parse".to_string(), &b.parse))
+            .chain(b.parts.iter().map(|(p, m)| (p.to_string(), m)))
+            .filter_map(move |(phase, m)| Some((b.day, phase, input, m.as_ref().ok()?)))
+    })
+}
+
+impl Baseline {
+    pub const DEFAULT_PATH: &'static str = "./results/bench.json";
+
+    /// Read `path`; a missing file is an empty baseline.
+    pub fn load(path: &Path) -> Result<Self, BoxError> {
+        let records = match std::fs::read_to_string(path) {
+            Ok(text) => serde_json::from_str(&text).map_err(|e| format!("{}: {e}", path.display()))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(format!("{}: {e}", path.display()).into()),
+        };
+
+        Ok(Self { path: path.to_path_buf(), records })
+    }
+
+    pub fn save(&self) -> Result<(), BoxError> {
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+
+        std::fs::write(&self.path, serde_json::to_string_pretty(&self.records)?)?;
+
+        Ok(())
+    }
+
+    /// Store `benches` as the new baseline for their days, phases and inputs.
+    pub fn record(&mut self, year: u16, benches: &[DayBench]) {
+        for (day, phase, input, stats) in phases(benches) {
+            let new = Record::new(year, day, phase, input.to_string(), stats);
+
+            match self.records.iter_mut().find(|r| r.same_key(&new)) {
+                Some(old) => *old = new,
+                None => self.records.push(new),
+            }
+        }
+    }
+
+    /// Compare medians against the baseline; anything more than `threshold`
+    /// (e.g. `0.1` for 10%) slower is a regression.
+    pub fn compare(&self, year: u16, benches: &[DayBench], threshold: f64) -> Vec<Comparison> {
+        phases(benches).map(|(day, phase, input, stats)| {
+            let before = self.records.iter()
+                                     .find(|r| r.year == year && r.day == day && r.phase == phase && r.input == input)
+                                     .map(|r| Duration::from_nanos(r.median_ns));
+
+            let mut c = Comparison { day, phase, before, now: stats.median, regressed: false };
+            c.regressed = c.change().is_some_and(|ch| ch > threshold);
+            c
+        }).collect()
+    }
+}
+
+pub fn comparison_table(comparisons: &[Comparison]) -> String {
+    let mut out = String::new();
+    write_comparison(&mut out, comparisons).expect("Writing to a String can't fail");
+    out
+}
+
+fn write_comparison(out: &mut String, comparisons: &[Comparison]) -> fmt::Result {
+    writeln!(out, "{:>3}  {:<5}  {:>10}  {:>10}  {:>8}", "Day", "Phase", "Baseline", "Median", "Change")?;
+
+    for c in comparisons {
+        let before = c.before.map_or("-".to_string(), |b| format!("{b:.2?}"));
+        let change = c.change().map_or("new".to_string(), |ch| format!("{:+.1}%", ch * 100.0));
+        let flag = if c.regressed { "  REGRESSION" } else { "" };
+
+        writeln!(out, "{:>3}  {:<5}  {before:>10}  {:>10}  {change:>8}{flag}",
+                 c.day, c.phase, format!("{:.2?}", c.now))?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::{path::PathBuf, time::Duration};
+
+    use super::Baseline;
+    use crate::{bench::{DayBench, Stats}, runner::Part};
+
+    fn bench(input: &str, median_us: u64) -> DayBench {
+        let d = Duration::from_micros(median_us);
+        let stats = Stats { runs: 1, min: d, max: d, median: d, mean: d, p95: d, stddev: Duration::ZERO };
+
+        DayBench {
+            day: 3,
+            title: "Test",
+            input_hash: Some(input.to_string()),
+            parse: Ok(stats),
+            parts: vec![(Part::A, Ok(stats)), (Part::B, Err("boom".to_string()))],
+            allocations: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn regression() {
+        let mut base = Baseline { path: PathBuf::new(), records: Vec::new() };
+        base.record(2023, &[bench("abc", 100)]);
+        base.record(2023, &[bench("abc", 100)]);
+        assert_eq!(base.records.len(), 2);
+
+        let same = base.compare(2023, &[bench("abc", 105)], 0.1);
+        assert!(same.iter().all(|c| !c.regressed && c.before.is_some()));
+
+        let slow = base.compare(2023, &[bench("abc", 150)], 0.1);
+        assert!(slow.iter().all(|c| c.regressed));
+
+        let other_input = base.compare(2023, &[bench("def", 150)], 0.1);
+        assert!(other_input.iter().all(|c| !c.regressed && c.before.is_none()));
+    }
+}
@@ -0,0 +1,169 @@
+//! `run --inputs`: run days against a whole directory of inputs at once.
+//!
+//! Each entry in the directory is someone's inputs: a file is the input for
+//! the one day being run, and a directory is laid out like `input/`. The
+//! answers for an input file `X` go next to it in `X.toml`, as `a = ..` and
+//! `b = ..`.
+
+use std::{path::{Path, PathBuf}, thread};
+
+use schema::Year;
+use serde::Serialize;
+
+use crate::{
+    answers::DayAnswers,
+    input::Source,
+    output::Tabular,
+    run::{self, DaySet, DayReport},
+    runner::{Outcome, Part, RunOptions},
+    verify::{self, Check},
+};
+
+#[derive(Debug, Serialize)]
+pub struct BatchRow {
+    /// The entry in the directory this came from.
+    pub input: String,
+    #[serde(flatten)]
+    pub check: Check,
+    pub duration_ns: Option<u64>,
+}
+
+impl Tabular for BatchRow {
+    const HEADERS: &'static [&'static str] = &["input", "year", "day", "title", "part", "status", "expected", "got", "duration_ns"];
+
+    fn cells(&self) -> Vec<String> {
+        let mut cells = vec![self.input.clone()];
+        cells.extend(self.check.cells());
+        cells.push(self.duration_ns.map(|n| n.to_string()).unwrap_or_default());
+        cells
+    }
+}
+
+/// Everyone's inputs in `dir`, by name, leaving out answer files and
+/// anything hidden.
+pub fn sources(dir: &Path) -> Result<Vec<(String, Source)>, String> {
+    let entries = std::fs::read_dir(dir).map_err(|e| format!("{}: {e}", dir.display()))?;
+    let mut sources = Vec::new();
+
+    for entry in entries {
+        let path = entry.map_err(|e| format!("{}: {e}", dir.display()))?.path();
+        let name = path.file_name().unwrap_or_default().to_string_lossy().into_owned();
+
+        if name.starts_with('.') || path.extension().is_some_and(|e| e == "toml") {
+            continue;
+        }
+
+        let source = match path.is_dir() {
+            true => Source::Dir(path),
+            false => Source::File(path),
+        };
+
+        sources.push((name, source));
+    }
+
+    sources.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    Ok(sources)
+}
+
+/// `input` with `.toml` on the end.
+fn answers_path(input: PathBuf) -> PathBuf {
+    let mut path = input.into_os_string();
+    path.push(".toml");
+    path.into()
+}
+
+fn rows(year: &Year, name: &str, source: &Source, report: &DayReport) -> Vec<BatchRow> {
+    let answers = match source.locate(year, report.day).map(answers_path).map(|p| DayAnswers::load(&p)) {
+        Some(Ok(a)) => a,
+        Some(Err(e)) => {
+            eprintln!("[WARN] {e}");
+            None
+        },
+        None => None,
+    };
+
+    verify::check(year.number(), report, |p| answers.as_ref()?.get(p))
+        .into_iter()
+        .zip([Part::A, Part::B])
+        .map(|(check, part)| BatchRow {
+            input: name.to_string(),
+            check,
+            duration_ns: match report.outcome(part) {
+                Some(Outcome::Solved(s)) => Some(s.elapsed.as_nanos() as u64),
+                _ => None,
+            },
+        })
+        .collect()
+}
+
+/// Run the [selected](run::select_days) days over every input in `dir`,
+/// all at once. Also returns the days asked for that have no solver.
+pub fn run_batch(year: &Year, dir: &Path, days: Option<&DaySet>, opts: &RunOptions)
+        -> Result<(Vec<BatchRow>, Vec<u8>), String> {
+    let sources = sources(dir)?;
+    let (selected, skipped) = run::select_days(year, days);
+
+    if selected.len() > 1 {
+        if let Some((name, _)) = sources.iter().find(|(_, s)| matches!(s, Source::File(_))) {
+            return Err(format!("`{name}` is a single input, so pick a single day to run it with"));
+        }
+    }
+
+    let rows = thread::scope(|scope| {
+        let handles: Vec<_> = sources.iter()
+            .flat_map(|(name, source)| selected.iter().map(move |&(d, s)| (name, source, d, s)))
+            .map(|(name, source, d, s)| {
+                (name, source, d, s, scope.spawn(move || run::run_day(year, source, d, s, &[Part::A, Part::B], opts)))
+            })
+            .collect();
+
+        handles.into_iter()
+               .flat_map(|(name, source, d, s, h)| rows(year, name, source, &run::join_day(h, d, s)))
+               .collect()
+    });
+
+    Ok((rows, skipped))
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::Path;
+
+    use schema::registry::Registry;
+
+    use super::run_batch;
+    use crate::{run::DaySet, runner::RunOptions, verify::Status};
+
+    #[test]
+    fn directory() {
+        let dir = std::env::temp_dir().join(format!("aoc-batch-test-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("carol/2023")).unwrap();
+
+        let history = "0 3 6 9 12 15\n1 3 6 10 15 21\n10 13 16 21 30 45\n";
+        std::fs::write(dir.join("alice"), history).unwrap();
+        std::fs::write(dir.join("alice.toml"), "a = 114\nb = \"2\"\n").unwrap();
+        std::fs::write(dir.join("bob"), history).unwrap();
+        std::fs::write(dir.join("bob.toml"), "a = 1\n").unwrap();
+        std::fs::write(dir.join("carol/2023/day09"), history).unwrap();
+
+        let registry = Registry::load().unwrap();
+        let year = registry.year(2023).unwrap();
+        let day9 = "9".parse::<DaySet>().unwrap();
+
+        let (rows, _) = run_batch(year, &dir, Some(&day9), &RunOptions::default()).unwrap();
+        let statuses: Vec<(&str, Status)> = rows.iter().map(|r| (r.input.as_str(), r.check.status)).collect();
+
+        assert_eq!(statuses, [
+            ("alice", Status::Pass), ("alice", Status::Pass),
+            ("bob", Status::Fail), ("bob", Status::Missing),
+            ("carol", Status::Missing), ("carol", Status::Missing),
+        ]);
+
+        let both = "8..=9".parse::<DaySet>().unwrap();
+        assert!(run_batch(year, &dir, Some(&both), &RunOptions::default()).is_err());
+        assert!(run_batch(year, Path::new("/no/such/dir"), None, &RunOptions::default()).is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,226 @@
+//! `bench`: time each phase of a day over many runs.
+
+use std::{
+    fmt::{self, Write as _},
+    panic::{self, AssertUnwindSafe},
+    time::Duration,
+};
+
+use schema::{alloc::Allocations, BoxError, Context, DynSolver, Parsed, Solution, Year};
+
+use crate::{input::{self, Source}, output, runner::Part};
+
+#[derive(Debug, Clone, Copy)]
+pub struct BenchOptions {
+    /// Untimed runs before measuring, to warm caches and the allocator.
+    pub warmup: usize,
+    /// Timed runs.
+    pub runs: usize,
+}
+
+/// Summary statistics over a set of timings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    pub runs: usize,
+    pub min: Duration,
+    pub max: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub p95: Duration,
+    pub stddev: Duration,
+}
+
+impl Stats {
+    pub fn from_samples(samples: &[Duration]) -> Option<Self> {
+        let mut sorted = samples.to_vec();
+        sorted.sort_unstable();
+
+        let n = sorted.len();
+        let (min, max) = (*sorted.first()?, *sorted.last()?);
+
+        let median = match n % 2 {
+            0 => (sorted[n / 2 - 1] + sorted[n / 2]) / 2,
+            _ => sorted[n / 2],
+        };
+
+        // Nearest rank.
+        let p95 = sorted[(n * 95).div_ceil(100) - 1];
+
+        let secs: Vec<f64> = sorted.iter().map(Duration::as_secs_f64).collect();
+        let mean = secs.iter().sum::<f64>() / n as f64;
+        let var = secs.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / n as f64;
+
+        Some(Self {
+            runs: n,
+            min,
+            max,
+            median,
+            mean: Duration::from_secs_f64(mean),
+            p95,
+            stddev: Duration::from_secs_f64(var.sqrt()),
+        })
+    }
+}
+
+/// What got measured for one phase, or why it couldn't be.
+pub type Measured = Result<Stats, String>;
+
+pub struct DayBench {
+    pub day: u8,
+    pub title: &'static str,
+    /// [Hash](input::hash) of the input measured, if it could be read.
+    pub input_hash: Option<String>,
+    pub parse: Measured,
+    pub parts: Vec<(Part, Measured)>,
+    /// What each part allocated on its last run, if the binary counts.
+    pub allocations: Vec<(Part, Allocations)>,
+}
+
+impl DayBench {
+    pub fn allocations(&self, part: Part) -> Option<Allocations> {
+        self.allocations.iter().find(|(p, _)| *p == part).map(|(_, a)| *a)
+    }
+}
+
+/// Run `f` `warmup + runs` times, keeping the timings of the last `runs`.
+///
+/// Stops at the first error or panic.
+fn measure(opts: &BenchOptions, mut f: impl FnMut() -> Result<Duration, BoxError>) -> Measured {
+    let mut samples = Vec::with_capacity(opts.runs);
+
+    for i in 0..opts.warmup + opts.runs {
+        let elapsed = match panic::catch_unwind(AssertUnwindSafe(&mut f)) {
+            Ok(Ok(t)) => t,
+            Ok(Err(e)) => return Err(e.to_string()),
+            Err(_) => return Err("panicked".to_string()),
+        };
+
+        if i >= opts.warmup {
+            samples.push(elapsed);
+        }
+    }
+
+    Stats::from_samples(&samples).ok_or_else(|| "no runs".to_string())
+}
+
+fn part(parsed: &Parsed<'_>, part: Part) -> Result<Solution, BoxError> {
+    let ctx = Context::default();
+
+    match part {
+        Part::A => parsed.part_a(&ctx),
+        Part::B => parsed.part_b(&ctx),
+    }
+}
+
+/// Benchmark parsing and both parts of `day`, on its input from `source`.
+///
+/// Parts run one at a time on this thread so they don't compete with each
+/// other.
+pub fn bench_day(year: &Year, source: &Source, day: u8, solver: &dyn DynSolver, opts: &BenchOptions) -> DayBench {
+    let title = solver.title();
+
+    let fail = |input_hash, why: String| DayBench {
+        day, title, input_hash, parse: Err(why), parts: Vec::new(), allocations: Vec::new(),
+    };
+
+    let input = match source.read(year, day) {
+        Ok(i) => i,
+        Err(e) => return fail(None, e),
+    };
+
+    let input_hash = Some(input::hash(&input));
+
+    let parse = measure(opts, || Ok(solver.prepare(&input)?.elapsed));
+
+    let parsed = match solver.prepare(&input) {
+        Ok(p) => p,
+        Err(e) => return fail(input_hash, e.to_string()),
+    };
+
+    let mut allocations = Vec::new();
+
+    let parts = [Part::A, Part::B].into_iter()
+        .map(|p| {
+            let mut last = None;
+
+            let measured = measure(opts, || part(&parsed, p).map(|s| {
+                last = s.allocations;
+                s.elapsed
+            }));
+
+            allocations.extend(last.map(|a| (p, a)));
+            (p, measured)
+        })
+        .collect();
+
+    DayBench { day, title, input_hash, parse, parts, allocations }
+}
+
+/// Render one row per phase.
+pub fn table(benches: &[DayBench]) -> String {
+    let mut out = String::new();
+    write_table(&mut out, benches).expect("Writing to a String can't fail");
+    out
+}
+
+fn write_table(out: &mut String, benches: &[DayBench]) -> fmt::Result {
+    let title_w = benches.iter().map(|b| b.title.len()).max().unwrap_or(0).max(5);
+
+    // Only when the binary counts allocations.
+    let counted = benches.iter().any(|b| !b.allocations.is_empty());
+
+    let memory = |a: Option<Allocations>| match (counted, a) {
+        (false, _) => String::new(),
+        (true, Some(a)) => format!("  {:>10}  {:>10}  {:>10}", a.count, output::bytes(a.bytes), output::bytes(a.peak)),
+        (true, None) => format!("  {:>10}  {:>10}  {:>10}", "-", "-", "-"),
+    };
+
+    let header = match counted {
+        true => format!("  {:>10}  {:>10}  {:>10}", "Allocs", "Allocated", "Peak"),
+        false => String::new(),
+    };
+
+    writeln!(out, "{:>3}  {:<title_w$}  {:<5}  {:>5}  {:>10}  {:>10}  {:>10}  {:>10}  {:>10}{header}",
+             "Day", "Title", "Phase", "Runs", "Min", "Median", "Mean", "p95", "Stddev")?;
+
+    for b in benches {
+        let phases = std::iter::once(("Parse".to_string(), &b.parse, None))
+            .chain(b.parts.iter().map(|(p, m)| (p.to_string(), m, b.allocations(*p))));
+
+        for (phase, measured, allocations) in phases {
+            match measured {
+                Ok(s) => writeln!(out, "{:>3}  {:<title_w$}  {phase:<5}  {:>5}  {:>10}  {:>10}  {:>10}  {:>10}  {:>10}{}",
+                                  b.day, b.title, s.runs,
+                                  format!("{:.2?}", s.min), format!("{:.2?}", s.median),
+                                  format!("{:.2?}", s.mean), format!("{:.2?}", s.p95),
+                                  format!("{:.2?}", s.stddev), memory(allocations))?,
+                Err(e) => writeln!(out, "{:>3}  {:<title_w$}  {phase:<5}  {e}", b.day, b.title)?,
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::Stats;
+
+    #[test]
+    fn stats() {
+        let ms = |n| Duration::from_millis(n);
+        let samples: Vec<Duration> = [5, 1, 4, 2, 3, 100].into_iter().map(ms).collect();
+
+        let s = Stats::from_samples(&samples).unwrap();
+
+        assert_eq!(s.runs, 6);
+        assert_eq!(s.min, ms(1));
+        assert_eq!(s.max, ms(100));
+        assert_eq!(s.median, Duration::from_micros(3500));
+        assert_eq!(s.p95, ms(100));
+        assert_eq!(s.mean.as_millis(), 19);
+        assert!(Stats::from_samples(&[]).is_none());
+    }
+}
@@ -0,0 +1,301 @@
+//! Answers from earlier runs, so days whose input and code haven't changed
+//! needn't be solved again.
+//!
+//! An entry is keyed by year, day, part, the [hash](input::hash) of the
+//! input and the build: a hash of the running binary's size and
+//! modification time, so any rebuild misses. Entries live in
+//! `<root>/<year>/day<NN>/<part>-<input>-<build>.json`.
+
+use std::{path::{Path, PathBuf}, time::{Duration, UNIX_EPOCH}};
+
+use schema::{Answer, BoxError, Solution};
+use serde::{Deserialize, Serialize};
+
+use crate::{input, runner::{Outcome, Part}};
+
+#[derive(Serialize, Deserialize)]
+struct Entry {
+    answer: Answer,
+    /// How long it took when it was solved.
+    elapsed_ns: u64,
+    diagnostics: Vec<String>,
+}
+
+/// Tells builds of the binary at `exe` apart by its size and modification
+/// time, rather than reading the whole thing to hash it.
+fn build_id(exe: &Path) -> Result<String, BoxError> {
+    let meta = std::fs::metadata(exe).map_err(|e| format!("{}: {e}", exe.display()))?;
+    let modified = meta.modified()?.duration_since(UNIX_EPOCH).unwrap_or_default();
+
+    Ok(input::hash(format!("{}-{}", meta.len(), modified.as_nanos())))
+}
+
+pub struct Cache {
+    root: PathBuf,
+    build: String,
+}
+
+impl Cache {
+    pub const DEFAULT_ROOT: &'static str = "./cache";
+
+    pub fn new(root: &Path, build: &str) -> Self {
+        Self { root: root.to_path_buf(), build: build.to_string() }
+    }
+
+    /// The cache under `root` for the running binary.
+    pub fn open(root: &Path) -> Result<Self, BoxError> {
+        Ok(Self::new(root, &build_id(&std::env::current_exe()?)?))
+    }
+
+    fn dir(&self, year: u16, day: u8) -> PathBuf {
+        self.root.join(year.to_string()).join(format!("day{day:02}"))
+    }
+
+    /// Everything but the build, so older builds' entries can be found.
+    fn prefix(part: Part, input: &str) -> String {
+        format!("{part}-{input}-")
+    }
+
+    fn path(&self, year: u16, day: u8, part: Part, input: &str) -> PathBuf {
+        self.dir(year, day).join(format!("{}{}.json", Self::prefix(part, input), self.build))
+    }
+
+    fn get(&self, year: u16, day: u8, part: Part, input: &str) -> Option<Solution> {
+        let text = std::fs::read_to_string(self.path(year, day, part, input)).ok()?;
+        let entry: Entry = serde_json::from_str(&text).ok()?;
+
+        Some(Solution {
+            answer: entry.answer,
+            elapsed: Duration::from_nanos(entry.elapsed_ns),
+            diagnostics: entry.diagnostics,
+            allocations: None,
+        })
+    }
+
+    /// The `parts` with a cached answer for the input hashed to `input`,
+    /// and the ones without.
+    pub fn lookup(&self, year: u16, day: u8, input: &str, parts: &[Part]) -> (Vec<(Part, Outcome)>, Vec<Part>) {
+        let mut hits = Vec::new();
+        let mut misses = Vec::new();
+
+        for &part in parts {
+            match self.get(year, day, part, input) {
+                Some(s) => hits.push((part, Outcome::Solved(s))),
+                None => misses.push(part),
+            }
+        }
+
+        (hits, misses)
+    }
+
+    fn put(&self, year: u16, day: u8, part: Part, input: &str, solution: &Solution) -> Result<(), BoxError> {
+        let dir = self.dir(year, day);
+        std::fs::create_dir_all(&dir)?;
+
+        // Other builds' answers for this input won't be wanted again.
+        let prefix = Self::prefix(part, input);
+
+        for entry in std::fs::read_dir(&dir)? {
+            let path = entry?.path();
+
+            if path.file_name().is_some_and(|n| n.to_string_lossy().starts_with(&prefix)) {
+                std::fs::remove_file(path)?;
+            }
+        }
+
+        let entry = Entry {
+            answer: solution.answer.clone(),
+            elapsed_ns: solution.elapsed.as_nanos() as u64,
+            diagnostics: solution.diagnostics.clone(),
+        };
+
+        std::fs::write(self.path(year, day, part, input), serde_json::to_string(&entry)?)?;
+
+        Ok(())
+    }
+
+    /// Save the parts that were solved. Failures, timeouts and unfinished
+    /// parts aren't worth keeping.
+    pub fn store(&self, year: u16, day: u8, input: &str, outcomes: &[(Part, Outcome)]) {
+        for (part, outcome) in outcomes {
+            let Outcome::Solved(s) = outcome else { continue };
+
+            if matches!(s.answer, Answer::Unimplemented) {
+                continue;
+            }
+
+            if let Err(e) = self.put(year, day, *part, input, s) {
+                eprintln!("[WARN] Couldn't cache day {day} part {part}: {e}");
+            }
+        }
+    }
+
+    /// Delete the answers under `root`, returning how many went. Anything
+    /// else in there is left alone, along with the directories holding it.
+    pub fn clear(root: &Path) -> Result<usize, BoxError> {
+        let mut count = 0;
+
+        for year in subdirs(root, |n| n.parse::<u16>().is_ok())? {
+            for day in subdirs(&year, |n| n.strip_prefix("day").is_some_and(|d| d.parse::<u8>().is_ok()))? {
+                for entry in std::fs::read_dir(&day)? {
+                    let path = entry?.path();
+
+                    if path.is_file() && path.file_name().and_then(|n| n.to_str()).is_some_and(is_entry) {
+                        std::fs::remove_file(&path).map_err(|e| format!("{}: {e}", path.display()))?;
+                        count += 1;
+                    }
+                }
+
+                // These only go if they're now empty.
+                let _ = std::fs::remove_dir(&day);
+            }
+
+            let _ = std::fs::remove_dir(&year);
+        }
+
+        let _ = std::fs::remove_dir(root);
+
+        Ok(count)
+    }
+}
+
+/// Whether `name` is an entry's file name, `<part>-<input>-<build>.json`.
+fn is_entry(name: &str) -> bool {
+    let key = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_alphanumeric());
+
+    let Some(stem) = name.strip_suffix(".json") else { return false };
+
+    match stem.split('-').collect::<Vec<_>>()[..] {
+        [part, input, build] => matches!(part, "A" | "B") && key(input) && key(build),
+        _ => false,
+    }
+}
+
+/// The directories in `dir` whose names pass `keep`; none if `dir` doesn't
+/// exist.
+fn subdirs(dir: &Path, keep: impl Fn(&str) -> bool) -> Result<Vec<PathBuf>, BoxError> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("{}: {e}", dir.display()).into()),
+    };
+
+    let mut dirs = Vec::new();
+
+    for entry in entries {
+        let path = entry?.path();
+
+        if path.is_dir() && path.file_name().and_then(|n| n.to_str()).is_some_and(&keep) {
+            dirs.push(path);
+        }
+    }
+
+    Ok(dirs)
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use schema::{registry::Registry, Answer, Solution};
+
+    use super::{build_id, Cache};
+    use crate::{input::Source, run::{self, DayResult}, runner::{Outcome, Part, RunOptions}};
+
+    fn solved(answer: Answer) -> Outcome {
+        Outcome::Solved(Solution::new(answer, Duration::from_millis(3)))
+    }
+
+    #[test]
+    fn builds() {
+        let exe = std::env::temp_dir().join(format!("aoc-cache-build-{}", std::process::id()));
+        std::fs::write(&exe, "build").unwrap();
+
+        let first = build_id(&exe).unwrap();
+        assert_eq!(build_id(&exe).unwrap(), first);
+
+        std::fs::write(&exe, "rebuilt").unwrap();
+        assert_ne!(build_id(&exe).unwrap(), first);
+
+        std::fs::remove_file(&exe).unwrap();
+        assert!(build_id(&exe).is_err());
+    }
+
+    #[test]
+    fn keys() {
+        let root = std::env::temp_dir().join(format!("aoc-cache-test-{}", std::process::id()));
+        let cache = Cache::new(&root, "build1");
+
+        cache.store(2023, 5, "input1", &[
+            (Part::A, solved(Answer::Number(35))),
+            (Part::B, Outcome::Failed("nope".into())),
+        ]);
+        cache.store(2023, 6, "input1", &[(Part::A, solved(Answer::Unimplemented))]);
+
+        let (hits, misses) = cache.lookup(2023, 5, "input1", &[Part::A, Part::B]);
+        assert!(matches!(&hits[..], [(Part::A, Outcome::Solved(s))]
+                         if s.answer == Answer::Number(35) && s.elapsed == Duration::from_millis(3)));
+        assert_eq!(misses, [Part::B]);
+
+        assert!(cache.lookup(2023, 5, "input2", &[Part::A]).0.is_empty());
+        assert!(cache.lookup(2023, 6, "input1", &[Part::A]).0.is_empty());
+
+        // A new build misses, and replaces the old build's answer.
+        let rebuilt = Cache::new(&root, "build2");
+        assert!(rebuilt.lookup(2023, 5, "input1", &[Part::A]).0.is_empty());
+
+        rebuilt.store(2023, 5, "input1", &[(Part::A, solved(Answer::Number(35)))]);
+        assert!(cache.lookup(2023, 5, "input1", &[Part::A]).0.is_empty());
+
+        assert_eq!(Cache::clear(&root).unwrap(), 1);
+        assert!(!root.exists());
+        assert_eq!(Cache::clear(&root).unwrap(), 0);
+    }
+
+    #[test]
+    fn clears_only_answers() {
+        let root = std::env::temp_dir().join(format!("aoc-cache-clear-test-{}", std::process::id()));
+        let cache = Cache::new(&root, "build1");
+
+        cache.store(2023, 5, "input1", &[(Part::A, solved(Answer::Number(35)))]);
+        cache.store(2023, 9, "input1", &[(Part::B, solved(Answer::Number(2)))]);
+
+        let day5 = root.join("2023").join("day05");
+        let keep = [root.join("notes.txt"), root.join("src").join("main.rs"), day5.join("A-notes.txt")];
+
+        for path in &keep {
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, "mine").unwrap();
+        }
+
+        assert_eq!(Cache::clear(&root).unwrap(), 2);
+        assert!(keep.iter().all(|p| p.exists()));
+        assert!(!root.join("2023").join("day09").exists());
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn skips_cached_days() {
+        let root = std::env::temp_dir().join(format!("aoc-cache-run-test-{}", std::process::id()));
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::write(root.join("day9"), "0 3 6 9 12 15\n1 3 6 10 15 21\n10 13 16 21 30 45\n").unwrap();
+
+        let registry = Registry::load().unwrap();
+        let year = registry.year(2023).unwrap();
+        let source = Source::File(root.join("day9"));
+
+        let cache = Cache::new(&root.join("cache"), "build");
+        let opts = RunOptions { cache: Some(&cache), ..RunOptions::default() };
+        let run = || run::run_day(year, &source, 9, year.get(9).unwrap(), &[Part::A, Part::B], &opts);
+
+        let first = run();
+        assert!(matches!(first.result, DayResult::Ran { parse: Some(_), ref cached, .. } if cached.is_empty()));
+
+        let second = run();
+        assert!(matches!(second.result, DayResult::Ran { parse: None, ref cached, .. } if cached == &[Part::A, Part::B]));
+        assert!(matches!(second.outcome(Part::A), Some(Outcome::Solved(s)) if s.answer == Answer::Number(114)));
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,291 @@
+//! Talking to the puzzle server: fetching inputs and submitting answers.
+//!
+//! The session token is the `session` cookie from a logged in browser. It
+//! comes from `AOC_SESSION`, or `session = ".."` in `aoc.toml`, which also
+//! takes a `base_url` to point the client somewhere else.
+
+use std::{fmt, path::{Path, PathBuf}, time::Duration};
+
+use schema::{BoxError, Year};
+use serde::Deserialize;
+use ureq::Agent;
+
+use crate::runner::Part;
+
+#[cfg(test)]
+mod mock;
+
+#[derive(Debug, Default, Deserialize)]
+pub struct Config {
+    pub session: Option<String>,
+    pub base_url: Option<String>,
+}
+
+impl Config {
+    pub const PATH: &'static str = "./aoc.toml";
+
+    /// Read `path`, if it's there; `AOC_SESSION` beats its `session`.
+    pub fn load(path: &Path) -> Result<Self, BoxError> {
+        let mut config: Self = match std::fs::read_to_string(path) {
+            Ok(text) => toml::from_str(&text).map_err(|e| format!("{}: {e}", path.display()))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Self::default(),
+            Err(e) => return Err(format!("{}: {e}", path.display()).into()),
+        };
+
+        if let Ok(session) = std::env::var("AOC_SESSION") {
+            config.session = Some(session);
+        }
+
+        Ok(config)
+    }
+
+    pub fn client(&self) -> Result<Client, BoxError> {
+        let session = self.session.as_deref()
+                                  .filter(|s| !s.trim().is_empty())
+                                  .ok_or("No session token; set AOC_SESSION or `session` in aoc.toml")?;
+
+        Ok(Client::new(self.base_url.as_deref().unwrap_or(Client::BASE_URL), session.trim()))
+    }
+}
+
+/// What the server made of a submitted answer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    TooHigh,
+    TooLow,
+    /// Wrong, without a hint which way.
+    Wrong,
+    /// Answered too recently; try again after the wait, if it said.
+    RateLimited(Option<Duration>),
+    /// The part was already solved, or isn't unlocked yet.
+    WrongLevel,
+    /// Something else; the page's text.
+    Unknown(String),
+}
+
+impl Verdict {
+    /// Read the verdict out of the answer page.
+    pub fn parse(html: &str) -> Self {
+        let text = article(html);
+        let lower = text.to_lowercase();
+
+        if lower.contains("that's the right answer") {
+            Verdict::Correct
+        } else if lower.contains("too high") {
+            Verdict::TooHigh
+        } else if lower.contains("too low") {
+            Verdict::TooLow
+        } else if lower.contains("not the right answer") {
+            Verdict::Wrong
+        } else if lower.contains("answer too recently") {
+            Verdict::RateLimited(wait(&lower))
+        } else if lower.contains("right level") {
+            Verdict::WrongLevel
+        } else {
+            Verdict::Unknown(text)
+        }
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Correct => write!(f, "correct"),
+            Verdict::TooHigh => write!(f, "wrong, too high"),
+            Verdict::TooLow => write!(f, "wrong, too low"),
+            Verdict::Wrong => write!(f, "wrong"),
+            Verdict::RateLimited(Some(d)) => write!(f, "rate limited; wait {}s", d.as_secs()),
+            Verdict::RateLimited(None) => write!(f, "rate limited"),
+            Verdict::WrongLevel => write!(f, "already solved, or not unlocked yet"),
+            Verdict::Unknown(text) => write!(f, "unrecognised response: {text}"),
+        }
+    }
+}
+
+/// The text of the page's `<article>`, or of the whole page, without tags.
+fn article(html: &str) -> String {
+    let body = match (html.find("<article"), html.find("</article>")) {
+        (Some(start), Some(end)) if start < end => &html[start..end],
+        _ => html,
+    };
+
+    let mut text = String::new();
+    let mut in_tag = false;
+
+    for c in body.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => {},
+        }
+    }
+
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// The wait in "you have 1m 5s left to wait".
+fn wait(text: &str) -> Option<Duration> {
+    let start = text.find("you have ")? + "you have ".len();
+    let end = start + text[start..].find(" left to wait")?;
+
+    text[start..end].split_whitespace().try_fold(Duration::ZERO, |total, part| {
+        let (n, unit) = part.split_at(part.find(|c: char| !c.is_ascii_digit())?);
+        let n: u64 = n.parse().ok()?;
+
+        match unit {
+            "h" => Some(total + Duration::from_secs(n * 3600)),
+            "m" => Some(total + Duration::from_secs(n * 60)),
+            "s" => Some(total + Duration::from_secs(n)),
+            _ => None,
+        }
+    })
+}
+
+pub struct Client {
+    base: String,
+    session: String,
+    agent: Agent,
+}
+
+impl Client {
+    pub const BASE_URL: &'static str = "https://adventofcode.com";
+
+    /// Who's asking, as the server would like automated tools to say.
+    const USER_AGENT: &'static str = "aoc_2023 runner (github.com/jleflang/Advent-Of-Code)";
+
+    pub fn new(base: &str, session: &str) -> Self {
+        let agent = Agent::config_builder()
+            .http_status_as_error(false)
+            .user_agent(Self::USER_AGENT)
+            .timeout_global(Some(Duration::from_secs(30)))
+            .build()
+            .into();
+
+        Self { base: base.trim_end_matches('/').to_string(), session: session.to_string(), agent }
+    }
+
+    fn cookie(&self) -> String {
+        format!("session={}", self.session)
+    }
+
+    /// Download `day`'s input.
+    pub fn fetch_input(&self, year: u16, day: u8) -> Result<String, BoxError> {
+        let url = format!("{}/{year}/day/{day}/input", self.base);
+
+        let mut response = self.agent.get(&url).header("Cookie", &self.cookie()).call()?;
+        let body = response.body_mut().read_to_string()?;
+
+        match response.status().as_u16() {
+            200 => Ok(body),
+            400 | 500 => Err(format!("{url}: the session token was refused").into()),
+            404 => Err(format!("{url}: not found; is {year} day {day} unlocked yet?").into()),
+            status => Err(format!("{url}: {status} {}", body.trim()).into()),
+        }
+    }
+
+    /// `day`'s input under `root`, downloading it first if it isn't there
+    /// yet. Also says whether it had to.
+    pub fn cached_input(&self, year: &Year, day: u8, root: &Path) -> Result<(PathBuf, bool), BoxError> {
+        let path = year.input_path(root, day);
+
+        if path.exists() {
+            return Ok((path, false));
+        }
+
+        let input = self.fetch_input(year.number(), day)?;
+
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+
+        std::fs::write(&path, input)?;
+
+        Ok((path, true))
+    }
+
+    pub fn submit(&self, year: u16, day: u8, part: Part, answer: &str) -> Result<Verdict, BoxError> {
+        let url = format!("{}/{year}/day/{day}/answer", self.base);
+        let level = match part {
+            Part::A => "1",
+            Part::B => "2",
+        };
+
+        let mut response = self.agent.post(&url)
+                                     .header("Cookie", &self.cookie())
+                                     .send_form([("level", level), ("answer", answer)])?;
+        let body = response.body_mut().read_to_string()?;
+
+        match response.status().as_u16() {
+            200 => Ok(Verdict::parse(&body)),
+            400 | 500 => Err(format!("{url}: the session token was refused").into()),
+            status => Err(format!("{url}: {status} {}", body.trim()).into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use schema::Year;
+
+    use super::{mock::{MockServer, Route}, Client, Verdict};
+    use crate::runner::Part;
+
+    fn page(text: &str) -> String {
+        format!("<html><body><main><article><p>{text}</p></article></main></body></html>")
+    }
+
+    #[test]
+    fn verdicts() {
+        let cases = [
+            ("That's the right answer! You are one gold star closer.", Verdict::Correct),
+            ("That's not the right answer; your answer is too high.", Verdict::TooHigh),
+            ("That's not the right answer; your answer is too low.", Verdict::TooLow),
+            ("That's not the right answer. If you're stuck, ...", Verdict::Wrong),
+            ("You gave an answer too recently. You have 1m 5s left to wait.",
+             Verdict::RateLimited(Some(Duration::from_secs(65)))),
+            ("You don't seem to be solving the right level. Did you already complete it?", Verdict::WrongLevel),
+        ];
+
+        for (text, verdict) in cases {
+            assert_eq!(Verdict::parse(&page(text)), verdict, "{text}");
+        }
+
+        assert_eq!(Verdict::parse(&page("Huh <em>what</em>?")), Verdict::Unknown("Huh what?".to_string()));
+    }
+
+    #[test]
+    fn fetch_and_submit() {
+        let server = MockServer::start(vec![
+            Route::new("GET", "/2023/day/6/input", 200, "Time: 7\nDistance: 9\n"),
+            Route::new("GET", "/2023/day/25/input", 404, "Please don't repeatedly request this endpoint before it unlocks!"),
+            Route::new("POST", "/2023/day/6/answer", 200, &page("That's the right answer!")),
+        ]);
+
+        let client = Client::new(&server.url(), "abc123");
+
+        let root = std::env::temp_dir().join(format!("aoc-client-test-{}", std::process::id()));
+        let year = Year::new(2023);
+
+        let (path, fetched) = client.cached_input(&year, 6, &root).unwrap();
+        assert!(fetched);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "Time: 7\nDistance: 9\n");
+
+        // Second time comes off disk.
+        assert!(!client.cached_input(&year, 6, &root).unwrap().1);
+        assert!(client.fetch_input(2023, 25).unwrap_err().to_string().contains("unlocked"));
+
+        assert_eq!(client.submit(2023, 6, Part::B, "71503").unwrap(), Verdict::Correct);
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 3);
+        assert!(requests.iter().all(|r| r.cookie.as_deref() == Some("session=abc123")));
+        assert_eq!((requests[2].method.as_str(), requests[2].path.as_str()), ("POST", "/2023/day/6/answer"));
+        assert_eq!(requests[2].body, "level=2&answer=71503");
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
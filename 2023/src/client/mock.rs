@@ -0,0 +1,103 @@
+//! A stand-in puzzle server on localhost, so the client can be tested
+//! offline.
+
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    net::{TcpListener, TcpStream},
+    sync::{Arc, Mutex},
+    thread,
+};
+
+/// A canned response for one method and path.
+pub struct Route {
+    method: &'static str,
+    path: &'static str,
+    status: u16,
+    body: String,
+}
+
+impl Route {
+    pub fn new(method: &'static str, path: &'static str, status: u16, body: &str) -> Self {
+        Self { method, path, status, body: body.to_string() }
+    }
+}
+
+/// A request as the server saw it.
+#[derive(Debug, Clone)]
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    pub cookie: Option<String>,
+    pub body: String,
+}
+
+/// Serves its routes until the test process ends; anything else is a 404.
+pub struct MockServer {
+    port: u16,
+    requests: Arc<Mutex<Vec<Request>>>,
+}
+
+impl MockServer {
+    pub fn start(routes: Vec<Route>) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Couldn't bind the mock server");
+        let port = listener.local_addr().unwrap().port();
+        let requests = Arc::new(Mutex::new(Vec::new()));
+
+        let seen = Arc::clone(&requests);
+
+        thread::spawn(move || {
+            for stream in listener.incoming().map_while(Result::ok) {
+                serve(stream, &routes, &seen);
+            }
+        });
+
+        Self { port, requests }
+    }
+
+    pub fn url(&self) -> String {
+        format!("http://127.0.0.1:{}", self.port)
+    }
+
+    pub fn requests(&self) -> Vec<Request> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+/// Answer one request, closing the connection after it. It's logged in
+/// `seen` before the response goes out, so a client never sees a response
+/// to a request that isn't logged yet.
+fn serve(mut stream: TcpStream, routes: &[Route], seen: &Mutex<Vec<Request>>) -> Option<()> {
+    let mut reader = BufReader::new(stream.try_clone().ok()?);
+
+    let mut line = String::new();
+    reader.read_line(&mut line).ok()?;
+
+    let mut words = line.split_whitespace();
+    let (method, path) = (words.next()?.to_string(), words.next()?.to_string());
+
+    let (mut cookie, mut length) = (None, 0);
+
+    loop {
+        let mut header = String::new();
+        reader.read_line(&mut header).ok()?;
+
+        let Some((name, value)) = header.trim_end().split_once(':') else { break };
+
+        match name.to_ascii_lowercase().as_str() {
+            "cookie" => cookie = Some(value.trim().to_string()),
+            "content-length" => length = value.trim().parse().ok()?,
+            _ => {},
+        }
+    }
+
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body).ok()?;
+
+    let (status, text) = routes.iter()
+                               .find(|r| r.method == method && r.path == path)
+                               .map_or((404, "Not Found"), |r| (r.status, r.body.as_str()));
+
+    seen.lock().unwrap().push(Request { method, path, cookie, body: String::from_utf8_lossy(&body).into_owned() });
+
+    write!(stream, "HTTP/1.1 {status} Mock\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{text}", text.len()).ok()
+}
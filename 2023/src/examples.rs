@@ -0,0 +1,127 @@
+//! `day --example`: check a day against the examples from its puzzle text.
+
+use schema::{Answer, DynSolver, Example};
+use serde::Serialize;
+
+use crate::{output::Tabular, runner::{self, Outcome, Part, RunOptions}, verify::Status};
+
+#[derive(Debug, Serialize)]
+pub struct ExampleCheck {
+    pub year: u16,
+    pub day: u8,
+    pub example: &'static str,
+    pub part: String,
+    pub status: Status,
+    pub expected: String,
+    /// The answer, or what went wrong.
+    pub got: String,
+}
+
+impl Tabular for ExampleCheck {
+    const HEADERS: &'static [&'static str] = &["year", "day", "example", "part", "status", "expected", "got"];
+
+    fn cells(&self) -> Vec<String> {
+        vec![
+            self.year.to_string(),
+            self.day.to_string(),
+            self.example.to_string(),
+            self.part.clone(),
+            self.status.label().to_string(),
+            self.expected.clone(),
+            self.got.clone(),
+        ]
+    }
+}
+
+fn expected(example: &Example, part: Part) -> Option<Answer> {
+    match part {
+        Part::A => example.expected_a(),
+        Part::B => example.expected_b(),
+    }
+}
+
+/// Run `parts` of `solver` on its example called `name`, or all of them,
+/// wherever the example has an answer to check against.
+pub fn check(year: u16, day: u8, solver: &dyn DynSolver, name: Option<&str>, parts: &[Part], opts: &RunOptions)
+        -> Result<Vec<ExampleCheck>, String> {
+    let examples = solver.examples();
+
+    if examples.is_empty() {
+        return Err(format!("{year} day {day} has no examples"));
+    }
+
+    let selected: Vec<&Example> = examples.iter().filter(|e| name.is_none_or(|n| n == e.name)).collect();
+
+    if selected.is_empty() {
+        let names: Vec<&str> = examples.iter().map(|e| e.name).collect();
+        return Err(format!("{year} day {day} has no example `{}`; try {}", name.unwrap_or_default(), names.join(", ")));
+    }
+
+    let mut checks = Vec::new();
+
+    for example in selected {
+        let wanted: Vec<(Part, Answer)> = parts.iter().filter_map(|&p| Some((p, expected(example, p)?))).collect();
+
+        let check = |part: Part, expected: &Answer, status, got| ExampleCheck {
+            year, day,
+            example: example.name,
+            part: part.to_string(),
+            status,
+            expected: expected.to_string(),
+            got,
+        };
+
+        let parsed = match solver.prepare(example.input) {
+            Ok(p) => p,
+            Err(e) => {
+                checks.extend(wanted.iter().map(|(p, a)| check(*p, a, Status::Error, e.to_string())));
+                continue;
+            },
+        };
+
+        let run: Vec<Part> = wanted.iter().map(|(p, _)| *p).collect();
+
+        for ((part, outcome), (_, answer)) in runner::run_parts(&parsed, &run, opts).into_iter().zip(&wanted) {
+            let (status, got) = match outcome {
+                Outcome::Solved(s) if s.answer.matches(answer) => (Status::Pass, s.answer.to_string()),
+                Outcome::Solved(s) => (Status::Fail, s.answer.to_string()),
+                Outcome::Failed(e) => (Status::Error, e.to_string()),
+                Outcome::Panicked(m) => (Status::Error, format!("panicked: {m}")),
+                Outcome::Cancelled => (Status::Error, "timed out".to_string()),
+                Outcome::Killed(why) => (Status::Error, why),
+            };
+
+            checks.push(check(part, answer, status, got));
+        }
+    }
+
+    match checks.is_empty() {
+        true => Err(format!("{year} day {day}'s examples don't have answers for that yet")),
+        false => Ok(checks),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use schema::registry::Registry;
+
+    use super::check;
+    use crate::{runner::{Part, RunOptions}, verify::Status};
+
+    #[test]
+    fn registered_examples_pass() {
+        let registry = Registry::load().unwrap();
+        let opts = RunOptions::default();
+
+        for (day, solver) in registry.year(2023).unwrap().days().filter(|(_, s)| !s.examples().is_empty()) {
+            // Days fresh from `new` have an example without answers yet.
+            for c in check(2023, day, solver, None, &[Part::A, Part::B], &opts).unwrap_or_default() {
+                assert_eq!(c.status, Status::Pass, "day {day} `{}` part {}: got {}", c.example, c.part, c.got);
+            }
+        }
+
+        let day8 = registry.get(2023, 8).unwrap();
+        assert_eq!(check(2023, 8, day8, Some("path2"), &[Part::A, Part::B], &opts).unwrap().len(), 1);
+        assert!(check(2023, 8, day8, Some("nope"), &[Part::A], &opts).is_err());
+    }
+}
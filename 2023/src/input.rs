@@ -0,0 +1,118 @@
+//! Finding puzzle inputs: on disk, in one given file or on stdin.
+
+use std::{io::Read, path::{Path, PathBuf}};
+
+use schema::Year;
+use twox_hash::XxHash64;
+
+/// Where to read inputs from.
+#[derive(Debug, Clone)]
+pub enum Source {
+    /// `<root>/<year>/day<NN>`, or one of the older layouts under `root`.
+    Dir(PathBuf),
+    /// One file, whatever the day.
+    File(PathBuf),
+    Stdin,
+}
+
+impl Default for Source {
+    fn default() -> Self {
+        Source::Dir(PathBuf::from(Self::DEFAULT_ROOT))
+    }
+}
+
+impl Source {
+    pub const DEFAULT_ROOT: &'static str = "./input";
+
+    /// `--input` if given (`-` meaning stdin), else `--input-dir`, else
+    /// [`Source::DEFAULT_ROOT`].
+    pub fn from_args(input: Option<PathBuf>, dir: Option<PathBuf>) -> Self {
+        match input {
+            Some(p) if p == Path::new("-") => Source::Stdin,
+            Some(p) => Source::File(p),
+            None => dir.map_or_else(Source::default, Source::Dir),
+        }
+    }
+
+    /// Every path `day` could be at, best first: `<year>/day<NN>`, then
+    /// `<year>/day<N>` and the single year `day<N>`.
+    fn candidates(&self, year: &Year, day: u8) -> Vec<PathBuf> {
+        match self {
+            Source::Dir(root) => vec![
+                year.input_path(root, day),
+                root.join(year.number().to_string()).join(format!("day{day}")),
+                root.join(format!("day{day}")),
+            ],
+            Source::File(p) => vec![p.clone()],
+            Source::Stdin => Vec::new(),
+        }
+    }
+
+    /// Whether there's anything to read for `day`. Stdin always counts.
+    pub fn exists(&self, year: &Year, day: u8) -> bool {
+        matches!(self, Source::Stdin) || self.locate(year, day).is_some()
+    }
+
+    /// The file `day`'s input would be read from, if it's a file that exists.
+    pub fn locate(&self, year: &Year, day: u8) -> Option<PathBuf> {
+        self.candidates(year, day).into_iter().find(|p| p.exists())
+    }
+
+    /// Read `day`'s input, or say where it was looked for.
+    pub fn read(&self, year: &Year, day: u8) -> Result<String, String> {
+        if let Source::Stdin = self {
+            let mut input = String::new();
+            std::io::stdin().read_to_string(&mut input).map_err(|e| format!("stdin: {e}"))?;
+
+            return Ok(input);
+        }
+
+        match self.locate(year, day) {
+            Some(p) => std::fs::read_to_string(&p).map_err(|e| format!("{}: {e}", p.display())),
+            None => {
+                let tried: Vec<String> = self.candidates(year, day).iter().map(|p| p.display().to_string()).collect();
+                Err(format!("no input for {} day {day}; tried {}", year.number(), tried.join(", ")))
+            },
+        }
+    }
+}
+
+/// A short, stable fingerprint of an input, for keying stored results.
+pub fn hash(input: impl AsRef<[u8]>) -> String {
+    format!("{:016x}", XxHash64::oneshot(0, input.as_ref()))
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::{Path, PathBuf};
+
+    use schema::Year;
+
+    use super::Source;
+
+    #[test]
+    fn layouts() {
+        let root = std::env::temp_dir().join(format!("aoc-input-test-{}", std::process::id()));
+        std::fs::create_dir_all(root.join("2023")).unwrap();
+        std::fs::write(root.join("2023/day06"), "new").unwrap();
+        std::fs::write(root.join("2023/day7"), "unpadded").unwrap();
+        std::fs::write(root.join("day8"), "legacy").unwrap();
+
+        let year = Year::new(2023);
+        let source = Source::from_args(None, Some(root.clone()));
+
+        assert_eq!(source.read(&year, 6).as_deref(), Ok("new"));
+        assert_eq!(source.read(&year, 7).as_deref(), Ok("unpadded"));
+        assert_eq!(source.read(&year, 8).as_deref(), Ok("legacy"));
+
+        let missing = source.read(&year, 9).unwrap_err();
+        assert!(missing.contains(&root.join("2023/day09").display().to_string()), "{missing}");
+        assert!(!source.exists(&year, 9));
+
+        std::fs::remove_dir_all(&root).unwrap();
+
+        assert!(matches!(Source::from_args(Some(PathBuf::from("-")), None), Source::Stdin));
+        assert!(matches!(Source::from_args(Some(PathBuf::from("x")), Some(root)),
+                         Source::File(p) if p == Path::new("x")));
+    }
+}
@@ -0,0 +1,360 @@
+//! `--isolate`: run each part in a child process of this binary, so one
+//! that loops forever or eats all the memory can be killed without taking
+//! the rest of the run with it.
+//!
+//! The parent starts `worker <day> <part>` with the input on its stdin and
+//! reads a [`Report`] back as the last line of its stdout. `--timeout` is
+//! enforced by killing the child, and `--memory-limit` caps the child's
+//! address space (on unix).
+
+use std::{
+    io::{Read, Write},
+    panic::{self, AssertUnwindSafe},
+    path::Path,
+    process::{Child, Command, ExitStatus, Stdio},
+    thread,
+    time::{Duration, Instant},
+};
+
+use schema::{alloc::Allocations, Answer, Context, DynSolver, Solution};
+use serde::{Deserialize, Serialize};
+
+use crate::{output, runner::{self, Outcome, Part}};
+
+/// How often the parent checks on its children.
+const TICK: Duration = Duration::from_millis(20);
+
+/// What a child may use.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Limits {
+    /// Address space, in bytes.
+    pub memory: Option<u64>,
+}
+
+/// How one part went, as the worker tells it.
+#[derive(Debug, Serialize, Deserialize)]
+enum Reported {
+    Solved {
+        answer: Answer,
+        elapsed_ns: u64,
+        diagnostics: Vec<String>,
+        allocations: Option<Allocations>,
+    },
+    Failed(String),
+    Panicked(String),
+    Cancelled,
+}
+
+/// The worker's one line of output.
+#[derive(Debug, Serialize, Deserialize)]
+enum Report {
+    BadInput(String),
+    Ran {
+        parse_ns: u64,
+        outcome: Reported,
+    },
+}
+
+impl From<Outcome> for Reported {
+    fn from(outcome: Outcome) -> Self {
+        match outcome {
+            Outcome::Solved(s) => Reported::Solved {
+                answer: s.answer,
+                elapsed_ns: s.elapsed.as_nanos() as u64,
+                diagnostics: s.diagnostics,
+                allocations: s.allocations,
+            },
+            Outcome::Failed(e) => Reported::Failed(e.to_string()),
+            Outcome::Panicked(m) => Reported::Panicked(m),
+            Outcome::Cancelled => Reported::Cancelled,
+            Outcome::Killed(why) => Reported::Failed(why),
+        }
+    }
+}
+
+impl From<Reported> for Outcome {
+    fn from(reported: Reported) -> Self {
+        match reported {
+            Reported::Solved { answer, elapsed_ns, diagnostics, allocations } => Outcome::Solved(Solution {
+                answer,
+                elapsed: Duration::from_nanos(elapsed_ns),
+                diagnostics,
+                allocations,
+            }),
+            Reported::Failed(e) => Outcome::Failed(e.into()),
+            Reported::Panicked(m) => Outcome::Panicked(m),
+            Reported::Cancelled => Outcome::Cancelled,
+        }
+    }
+}
+
+/// The child side: read the input from stdin, run `part` and print a
+/// [`Report`].
+pub fn worker(solver: &dyn DynSolver, part: Part, limits: &Limits) -> Result<(), String> {
+    if let Some(bytes) = limits.memory {
+        limit_memory(bytes)?;
+    }
+
+    let mut input = String::new();
+    std::io::stdin().read_to_string(&mut input).map_err(|e| format!("stdin: {e}"))?;
+
+    let report = match solver.prepare(&input) {
+        Err(e) => Report::BadInput(e.to_string()),
+        Ok(parsed) => {
+            let ctx = Context::default();
+
+            let solved = panic::catch_unwind(AssertUnwindSafe(|| match part {
+                Part::A => parsed.part_a(&ctx),
+                Part::B => parsed.part_b(&ctx),
+            }));
+
+            Report::Ran { parse_ns: parsed.elapsed.as_nanos() as u64, outcome: Outcome::from_join(solved).into() }
+        },
+    };
+
+    let line = serde_json::to_string(&report).map_err(|e| e.to_string())?;
+    println!("{line}");
+
+    Ok(())
+}
+
+#[cfg(unix)]
+fn limit_memory(bytes: u64) -> Result<(), String> {
+    let limit = libc::rlimit { rlim_cur: bytes as libc::rlim_t, rlim_max: bytes as libc::rlim_t };
+
+    // SAFETY: `setrlimit` only reads the struct we pass it.
+    match unsafe { libc::setrlimit(libc::RLIMIT_AS, &limit) } {
+        0 => Ok(()),
+        _ => Err(format!("couldn't limit memory: {}", std::io::Error::last_os_error())),
+    }
+}
+
+#[cfg(not(unix))]
+fn limit_memory(_bytes: u64) -> Result<(), String> {
+    Err("--memory-limit is only supported on unix".to_string())
+}
+
+/// How a child ended.
+struct Exit {
+    /// `None` if it was killed for running past the timeout.
+    status: Option<ExitStatus>,
+    stdout: String,
+    stderr: String,
+}
+
+fn drain(mut from: impl Read + Send + 'static) -> thread::JoinHandle<String> {
+    thread::spawn(move || {
+        let mut bytes = Vec::new();
+        let _ = from.read_to_end(&mut bytes);
+        String::from_utf8_lossy(&bytes).into_owned()
+    })
+}
+
+/// Start `cmd`, feed it `input` and wait for it, killing it if it's still
+/// going after `timeout`.
+fn supervise(mut cmd: Command, input: &str, timeout: Option<Duration>) -> Result<Exit, String> {
+    let mut child: Child = cmd.stdin(Stdio::piped())
+                              .stdout(Stdio::piped())
+                              .stderr(Stdio::piped())
+                              .spawn()
+                              .map_err(|e| format!("couldn't start a worker: {e}"))?;
+
+    let stdout = drain(child.stdout.take().expect("stdout is piped"));
+    let stderr = drain(child.stderr.take().expect("stderr is piped"));
+
+    // A child that dies before reading it all breaks the pipe; how it died
+    // says more than that does.
+    let _ = child.stdin.take().expect("stdin is piped").write_all(input.as_bytes());
+
+    let start = Instant::now();
+
+    let status = loop {
+        match child.try_wait().map_err(|e| e.to_string())? {
+            Some(status) => break Some(status),
+            None if timeout.is_some_and(|t| start.elapsed() >= t) => {
+                let _ = child.kill();
+                let _ = child.wait();
+                break None;
+            },
+            None => thread::sleep(TICK),
+        }
+    };
+
+    Ok(Exit {
+        status,
+        stdout: stdout.join().unwrap_or_default(),
+        stderr: stderr.join().unwrap_or_default(),
+    })
+}
+
+/// Why a child that didn't report died, as best its exit and stderr say.
+fn died(status: ExitStatus, stderr: &str, limits: &Limits) -> String {
+    let last = stderr.lines().rev().find(|l| !l.trim().is_empty()).unwrap_or_default().trim();
+
+    if stderr.contains("memory allocation of") || stderr.contains("out of memory") {
+        return match limits.memory {
+            Some(limit) => format!("ran out of memory (limit {})", output::bytes(limit)),
+            None => "ran out of memory".to_string(),
+        };
+    }
+
+    let how = match status.code() {
+        Some(code) => format!("worker exited with {code}"),
+        None => signal(status),
+    };
+
+    match last.is_empty() {
+        true => how,
+        false => format!("{how}: {last}"),
+    }
+}
+
+#[cfg(unix)]
+fn signal(status: ExitStatus) -> String {
+    use std::os::unix::process::ExitStatusExt;
+
+    match status.signal() {
+        Some(n) => format!("worker killed by signal {n}"),
+        None => format!("worker {status}"),
+    }
+}
+
+#[cfg(not(unix))]
+fn signal(status: ExitStatus) -> String {
+    format!("worker {status}")
+}
+
+/// Make sense of a child's [`Exit`]: the parse time and the part's outcome,
+/// or `Err` if the input didn't parse.
+fn outcome(exit: Exit, limits: &Limits) -> Result<(Option<Duration>, Outcome), String> {
+    let Some(status) = exit.status else {
+        return Ok((None, Outcome::Cancelled));
+    };
+
+    let report = exit.stdout.lines().next_back().and_then(|l| serde_json::from_str::<Report>(l).ok());
+
+    match report {
+        Some(Report::BadInput(e)) => Err(e),
+        Some(Report::Ran { parse_ns, outcome }) => Ok((Some(Duration::from_nanos(parse_ns)), outcome.into())),
+        None => Ok((None, Outcome::Killed(died(status, &exit.stderr, limits)))),
+    }
+}
+
+/// The `worker` command for `part` of `day`.
+fn worker_command(exe: &Path, year: u16, day: u8, part: Part, limits: &Limits) -> Command {
+    let mut cmd = Command::new(exe);
+    cmd.args(["--year", &year.to_string(), "worker", &day.to_string(), &part.to_string()]);
+
+    // Workers never look at the cache, so don't have them hash the binary
+    // for it.
+    cmd.env_remove("AOC_CACHE");
+
+    if let Some(bytes) = limits.memory {
+        cmd.args(["--memory-limit", &bytes.to_string()]);
+    }
+
+    cmd
+}
+
+/// The parse time, if any worker got that far, and each part's outcome.
+pub type Ran = (Option<Duration>, Vec<(Part, Outcome)>);
+
+/// Run `parts` of `day` on `input`, each in its own worker, all at once.
+/// `Err` if the input didn't parse.
+pub fn run_parts(year: u16, day: u8, input: &str, parts: &[Part], timeout: Option<Duration>, limits: &Limits)
+        -> Result<Ran, String> {
+    let exe = std::env::current_exe().map_err(|e| format!("can't find this binary to run workers: {e}"))?;
+
+    let exe = exe.as_path();
+
+    let exits: Vec<(Part, Result<Exit, String>)> = thread::scope(|s| {
+        let running: Vec<_> = parts.iter()
+                                   .map(|&part| (part, s.spawn(move || supervise(worker_command(exe, year, day, part, limits), input, timeout))))
+                                   .collect();
+
+        running.into_iter()
+               .map(|(part, h)| (part, h.join().unwrap_or_else(|p| Err(runner::panic_message(p)))))
+               .collect()
+    });
+
+    let mut parse = None;
+    let mut outcomes = Vec::new();
+
+    for (part, exit) in exits {
+        let (parsed, outcome) = match exit {
+            Ok(exit) => outcome(exit, limits)?,
+            Err(e) => (None, Outcome::Killed(e)),
+        };
+
+        parse = parse.or(parsed);
+        outcomes.push((part, outcome));
+    }
+
+    Ok((parse, outcomes))
+}
+
+#[cfg(all(test, unix))]
+mod test {
+    use std::{ffi::OsStr, path::Path, process::Command, time::{Duration, Instant}};
+
+    use schema::Answer;
+
+    use super::{outcome, supervise, worker_command, Limits, Report, Reported};
+    use crate::runner::{Outcome, Part};
+
+    fn sh(script: &str) -> Command {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", script]);
+        cmd
+    }
+
+    fn run(script: &str, timeout: Option<Duration>) -> Result<(Option<Duration>, Outcome), String> {
+        let limits = Limits { memory: Some(64 << 20) };
+        outcome(supervise(sh(script), "", timeout).unwrap(), &limits)
+    }
+
+    #[test]
+    fn reports() {
+        let line = |r: Report| serde_json::to_string(&r).unwrap();
+
+        let solved = line(Report::Ran {
+            parse_ns: 5,
+            outcome: Reported::Solved { answer: Answer::Number(42), elapsed_ns: 7, diagnostics: Vec::new(), allocations: None },
+        });
+
+        // Anything a solver printed comes first; the report is the last line.
+        let (parse, out) = run(&format!("echo noise; echo '{solved}'"), None).unwrap();
+        assert_eq!(parse, Some(Duration::from_nanos(5)));
+        assert!(matches!(out, Outcome::Solved(s) if s.answer == Answer::Number(42) && s.elapsed == Duration::from_nanos(7)));
+
+        let bad = line(Report::BadInput("line 1: nope".to_string()));
+        assert_eq!(run(&format!("echo '{bad}'"), None).err().as_deref(), Some("line 1: nope"));
+
+        let stdin = supervise(Command::new("cat"), "fed in", None).unwrap();
+        assert_eq!(stdin.stdout, "fed in");
+    }
+
+    #[test]
+    fn deaths() {
+        let start = Instant::now();
+        assert!(matches!(run("exec sleep 10", Some(Duration::from_millis(100))), Ok((None, Outcome::Cancelled))));
+        assert!(start.elapsed() < Duration::from_secs(5));
+
+        let oom = run("echo 'memory allocation of 1048576 bytes failed' >&2; kill -ABRT $$", None);
+        assert!(matches!(oom, Ok((None, Outcome::Killed(why))) if why == "ran out of memory (limit 64.0 MiB)"));
+
+        let crashed = run("echo 'oh no' >&2; kill -SEGV $$", None);
+        assert!(matches!(crashed, Ok((None, Outcome::Killed(why))) if why == "worker killed by signal 11: oh no"));
+
+        assert!(matches!(run("exit 3", None), Ok((None, Outcome::Killed(why))) if why == "worker exited with 3"));
+    }
+
+    #[test]
+    fn worker_skips_cache() {
+        let cmd = worker_command(Path::new("aoc"), 2023, 5, Part::B, &Limits { memory: Some(1024) });
+        let args: Vec<&OsStr> = cmd.get_args().collect();
+
+        assert_eq!(args, ["--year", "2023", "worker", "5", "B", "--memory-limit", "1024"]);
+        assert!(cmd.get_envs().any(|(k, v)| k == "AOC_CACHE" && v.is_none()));
+    }
+}
@@ -0,0 +1,116 @@
+use std::path::Path;
+
+use schema::{registry::Registry, BoxError, DynSolver};
+use serde::Serialize;
+
+use crate::{answers::AnswerStore, input::Source, output::{self, Tabular}, runner::Part, Format};
+
+/// How far along a part is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Status {
+    Done,
+    /// Written as a stub returning `Answer::Unimplemented`.
+    Stub,
+    /// The whole day is an `EmptySolver`.
+    Empty,
+}
+
+impl Status {
+    fn of(solver: &dyn DynSolver, part: Part) -> Self {
+        let (a, b) = solver.implemented();
+
+        match (solver.is_empty(), part) {
+            (true, _) => Status::Empty,
+            (false, Part::A) if !a => Status::Stub,
+            (false, Part::B) if !b => Status::Stub,
+            _ => Status::Done,
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            Status::Done => "yes",
+            Status::Stub => "stub",
+            Status::Empty => "empty",
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct Row {
+    year: u16,
+    day: u8,
+    title: &'static str,
+    part_a: Status,
+    part_b: Status,
+    input: bool,
+    expected_a: bool,
+    expected_b: bool,
+}
+
+impl Tabular for Row {
+    const HEADERS: &'static [&'static str] = &["year", "day", "title", "part_a", "part_b", "input", "expected_a", "expected_b"];
+
+    fn cells(&self) -> Vec<String> {
+        vec![
+            self.year.to_string(),
+            self.day.to_string(),
+            self.title.to_string(),
+            self.part_a.label().to_string(),
+            self.part_b.label().to_string(),
+            yes_no(self.input).to_string(),
+            yes_no(self.expected_a).to_string(),
+            yes_no(self.expected_b).to_string(),
+        ]
+    }
+}
+
+fn yes_no(b: bool) -> &'static str {
+    if b { "yes" } else { "no" }
+}
+
+/// Print every registered day of `year`, or of every year.
+pub fn list(registry: &Registry, source: &Source, year: Option<u16>, format: Format) -> Result<(), BoxError> {
+    let mut rows = Vec::new();
+
+    for y in registry.years().filter(|y| year.is_none_or(|n| n == y.number())) {
+        let answers = AnswerStore::load(Path::new(AnswerStore::DEFAULT_ROOT), y.number())?;
+
+        for (day, solver) in y.days() {
+            rows.push(Row {
+                year: y.number(),
+                day,
+                title: solver.title(),
+                part_a: Status::of(solver, Part::A),
+                part_b: Status::of(solver, Part::B),
+                input: source.exists(y, day),
+                expected_a: answers.get(day, Part::A).is_some(),
+                expected_b: answers.get(day, Part::B).is_some(),
+            });
+        }
+    }
+
+    match format {
+        Format::Table => {
+            let width = rows.iter().map(|r| r.title.len()).max().unwrap_or(0).max(5);
+
+            println!("{:<4}  {:>3}  {:<width$}  {:<5}  {:<5}  {:<5}  Expected", "Year", "Day", "Title", "A", "B", "Input");
+
+            for r in rows.iter() {
+                let expected = match (r.expected_a, r.expected_b) {
+                    (true, true) => "a, b",
+                    (true, false) => "a",
+                    (false, true) => "b",
+                    (false, false) => "-",
+                };
+
+                println!("{:<4}  {:>3}  {:<width$}  {:<5}  {:<5}  {:<5}  {expected}",
+                         r.year, r.day, r.title, r.part_a.label(), r.part_b.label(), yes_no(r.input));
+            }
+        },
+        f => print!("{}", output::render(f, &rows)?),
+    }
+
+    Ok(())
+}
@@ -1,109 +1,910 @@
-use std::{error::Error, sync::{Arc, Mutex}, };
+use std::{path::PathBuf, process::ExitCode, time::{Duration, Instant}};
 
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
+use schema::registry::Registry;
 
+// Other years are library crates in the workspace whose days use
+// `#[solver]`; depend on one and `use aoc_20XX as _;` here so it gets linked
+// in, and `--year` will find it.
+mod answers;
+mod baseline;
+mod batch;
+mod bench;
+mod cache;
+mod client;
+mod examples;
+mod input;
+mod isolate;
+mod list;
+mod output;
 mod problem;
+mod run;
+mod runner;
+mod scaffold;
+mod trace;
+mod verify;
+
+use answers::AnswerStore;
+use run::DaySet;
+use runner::{Outcome, Part, RunOptions};
+
+/// A part returned an error or panicked, or `verify` got a wrong answer.
+const EXIT_PART_FAILED: u8 = 1;
+/// Unknown year, day or part.
+const EXIT_USAGE: u8 = 2;
+/// The input file is missing or doesn't parse.
+const EXIT_INPUT: u8 = 3;
+/// A part was cancelled by `--timeout` (and nothing worse happened).
+const EXIT_TIMEOUT: u8 = 4;
+/// `bench --compare` found a phase slower than its baseline.
+const EXIT_REGRESSION: u8 = 5;
+/// The puzzle server couldn't be reached, refused us or asked us to wait.
+const EXIT_SERVER: u8 = 6;
+
+#[cfg(feature = "count-allocs")]
+#[global_allocator]
+static ALLOC: schema::alloc::CountingAlloc = schema::alloc::CountingAlloc;
 
 
 /// Advent of Code top level
 #[derive(Parser)]
 #[command(name = "Advent of Code", author = "James Leflang")]
 struct Cli {
-    /// Set the year
+    /// Event year to run, 2015 or later; defaults to the latest one with
+    /// solutions
+    #[arg(long, global = true, value_parser = clap::value_parser!(u16).range(schema::FIRST_YEAR as i64..))]
+    year: Option<u16>,
+
+    /// Directory holding `<year>/day<NN>` input files
+    #[arg(long, global = true, env = "AOC_INPUT_DIR")]
+    input_dir: Option<PathBuf>,
+
+    /// Reuse answers this build already found for the same input, and keep
+    /// new ones for next time
+    #[arg(long, global = true, env = "AOC_CACHE")]
+    cache: bool,
+
+    /// Solve everything again, even with `--cache` or `AOC_CACHE` set
+    #[arg(long, global = true)]
+    no_cache: bool,
+
+    /// Trace each day, its parse, parts and inner phases
+    #[arg(long, global = true, value_enum)]
+    trace: Option<trace::TraceFormat>,
+
+    /// Write the trace here instead of stderr (or `./trace.json` for
+    /// `chrome`)
+    #[arg(long, global = true, requires = "trace")]
+    trace_file: Option<PathBuf>,
+
     #[command(subcommand)]
     day: Days,
 }
 
 #[derive(Subcommand, Clone)]
 enum Days {
-    /// Valid Years
+    /// Run one day, or just one of its parts
     Day {
         day: u32,
 
-        part: Option<String>
+        part: Option<String>,
+
+        /// Read the input from this file instead, or from stdin for `-`
+        #[arg(long)]
+        input: Option<PathBuf>,
+
+        /// Check against the examples from the puzzle text instead, or just
+        /// the one named
+        #[arg(long, conflicts_with_all = ["input", "record", "isolate"])]
+        example: Option<Option<String>>,
+
+        /// Cancel parts still running after this many seconds
+        #[arg(long, value_parser = parse_seconds)]
+        timeout: Option<Duration>,
+
+        /// Run each part in its own process, killed at `--timeout`
+        #[arg(long)]
+        isolate: bool,
+
+        /// Cap each isolated part's address space at this many MiB
+        #[arg(long, requires = "isolate", value_parser = clap::value_parser!(u64).range(1..))]
+        memory_limit: Option<u64>,
+
+        /// Save the answers to `answers/<year>.toml`
+        #[arg(long)]
+        record: bool,
+
+        /// Output format
+        #[arg(long, value_enum, default_value_t = Format::Table)]
+        format: Format,
+    },
+
+    /// Run several days and print a summary
+    Run {
+        /// Days to run: `5`, `1..=10`, `3..7` or a list like `1,4,9..=12`
+        #[arg(required_unless_present = "all", conflicts_with = "all")]
+        days: Option<DaySet>,
+
+        /// Run every registered day
+        #[arg(long)]
+        all: bool,
+
+        /// Run the days concurrently instead of one after another
+        #[arg(long)]
+        parallel: bool,
+
+        /// Run over every input in this directory at once, checking each
+        /// against the answers in `<input>.toml` next to it
+        #[arg(long, conflicts_with_all = ["parallel", "record"])]
+        inputs: Option<PathBuf>,
+
+        /// Cancel parts still running after this many seconds
+        #[arg(long, value_parser = parse_seconds)]
+        timeout: Option<Duration>,
+
+        /// Run each part in its own process, killed at `--timeout`
+        #[arg(long)]
+        isolate: bool,
+
+        /// Cap each isolated part's address space at this many MiB
+        #[arg(long, requires = "isolate", value_parser = clap::value_parser!(u64).range(1..))]
+        memory_limit: Option<u64>,
+
+        /// Save the answers to `answers/<year>.toml`
+        #[arg(long)]
+        record: bool,
+
+        /// Output format
+        #[arg(long, value_enum, default_value_t = Format::Table)]
+        format: Format,
+    },
+
+    /// Time parsing and both parts over many runs
+    Bench {
+        /// Days to benchmark, in the same form as `run`
+        #[arg(required_unless_present = "all", conflicts_with = "all")]
+        days: Option<DaySet>,
+
+        /// Benchmark every registered day
+        #[arg(long)]
+        all: bool,
+
+        /// Untimed runs before measuring
+        #[arg(long, default_value_t = 3)]
+        warmup: usize,
+
+        /// Timed runs per phase
+        #[arg(long, default_value_t = 10, value_parser = clap::value_parser!(u64).range(1..))]
+        runs: u64,
+
+        /// Save the results as the new baseline
+        #[arg(long)]
+        save: bool,
+
+        /// Compare the results against the saved baseline
+        #[arg(long)]
+        compare: bool,
+
+        /// Percent slower than the baseline median that counts as a regression
+        #[arg(long, default_value_t = 10.0, value_parser = parse_percent)]
+        threshold: f64,
+
+        /// Results file to save to and compare against
+        #[arg(long, default_value = baseline::Baseline::DEFAULT_PATH)]
+        baseline: PathBuf,
+
+        /// Output format
+        #[arg(long, value_enum, default_value_t = Format::Table)]
+        format: Format,
+    },
+
+    /// Check answers against the ones saved with `--record`
+    Verify {
+        /// Days to check, in the same form as `run`; every registered day
+        /// if left out
+        days: Option<DaySet>,
+
+        /// Run the days concurrently instead of one after another
+        #[arg(long)]
+        parallel: bool,
+
+        /// Cancel parts still running after this many seconds
+        #[arg(long, value_parser = parse_seconds)]
+        timeout: Option<Duration>,
+
+        /// Output format
+        #[arg(long, value_enum, default_value_t = Format::Table)]
+        format: Format,
+    },
+
+    /// Download inputs that aren't on disk yet
+    Fetch {
+        /// Days to fetch, in the same form as `run`
+        days: DaySet,
+    },
+
+    /// Send an answer to the puzzle server
+    Submit {
+        day: u8,
+
+        /// `a` or `b`
+        part: Part,
+
+        /// The answer to send; solves the part on its input if left out
+        answer: Option<String>,
+    },
+
+    /// Start a new day from a template and register it
+    New {
+        #[arg(value_parser = clap::value_parser!(u8).range(1..=schema::LAST_DAY as i64))]
+        day: u8,
+
+        /// The puzzle's title
+        #[arg(long, default_value = "TODO")]
+        title: String,
+
+        /// Crate to add the day to; defaults to the nearest one holding a
+        /// `src/problem.rs`, from the current directory up
+        #[arg(long)]
+        dir: Option<PathBuf>,
+    },
+
+    /// Manage the answers kept by `--cache`
+    Cache {
+        #[command(subcommand)]
+        action: CacheAction,
+    },
+
+    /// Run one part on the input from stdin, for `--isolate`
+    #[command(hide = true)]
+    Worker {
+        day: u8,
+
+        part: Part,
+
+        /// Address space limit, in bytes
+        #[arg(long)]
+        memory_limit: Option<u64>,
+    },
+
+    /// Show every registered day and what's ready for it
+    List {
+        #[arg(long, value_enum, default_value_t = Format::Table)]
+        format: Format,
     },
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum Format {
+    /// Aligned columns for reading
+    Table,
+    /// JSON for scripts
+    Json,
+    /// CSV with a header row
+    Csv,
+    /// A Markdown table
+    Markdown,
+}
+
+#[derive(Subcommand, Clone)]
+enum CacheAction {
+    /// Delete every cached answer
+    Clear,
+}
+
+/// `--isolate` and `--memory-limit` as [`isolate::Limits`].
+fn isolation(isolate: bool, memory_mib: Option<u64>) -> Option<isolate::Limits> {
+    isolate.then_some(isolate::Limits { memory: memory_mib.map(|m| m << 20) })
+}
+
+fn parse_seconds(s: &str) -> Result<Duration, String> {
+    let secs: f64 = s.parse().map_err(|_| format!("`{s}` isn't a number of seconds"))?;
+
+    Duration::try_from_secs_f64(secs).map_err(|e| e.to_string())
+}
+
+fn parse_percent(s: &str) -> Result<f64, String> {
+    match s.parse::<f64>() {
+        Ok(p) if p.is_finite() && p >= 0.0 => Ok(p),
+        _ => Err(format!("`{s}` isn't a percentage of 0 or more")),
+    }
+}
+
+fn print_solution(part: &str, solution: &schema::Solution, cached: bool) {
+    let memory = match solution.allocations {
+        Some(a) => format!(", {} allocations, {} peak", a.count, output::bytes(a.peak)),
+        None => String::new(),
+    };
 
-    /// View Years
-    List,
+    match cached {
+        true => println!("Part {part} answer: {} (cached; took {:#?})", solution.answer, solution.elapsed),
+        false => println!("Part {part} answer: {} (took {:#?}{memory})", solution.answer, solution.elapsed),
+    }
+
+    // Display only falls back to the picture when OCR fails, so show it
+    // here too in case the letters were misread.
+    if let schema::Answer::Grid(g) = &solution.answer {
+        if g.ocr().is_some() {
+            println!("{g}");
+        }
+    }
+
+    for note in solution.diagnostics.iter() {
+        println!("    {note}");
+    }
+}
+
+/// Print `records` in `format`.
+fn print_records<T: output::Tabular>(format: Format, records: &[T]) {
+    match output::render(format, records) {
+        Ok(out) => print!("{out}"),
+        Err(e) => eprintln!("[ERROR] {e}"),
+    }
+}
+
+/// Load `year`'s answers, let `add` put new ones in and save them again.
+fn record_answers(year: u16, add: impl FnOnce(&mut AnswerStore) -> usize) -> Result<(), ExitCode> {
+    let root = std::path::Path::new(AnswerStore::DEFAULT_ROOT);
+
+    let saved = AnswerStore::load(root, year).and_then(|mut store| {
+        let n = add(&mut store);
+        store.save(root, year)?;
+        Ok(n)
+    });
+
+    match saved {
+        Ok(n) => {
+            eprintln!("Recorded {n} answer(s) in {}", AnswerStore::path(root, year).display());
+            Ok(())
+        },
+        Err(e) => {
+            eprintln!("[ERROR] Couldn't record answers: {e}");
+            Err(ExitCode::from(EXIT_INPUT))
+        },
+    }
 }
 
-fn main() -> Result<(), Box<dyn Error>>{
+/// Record the answers from a `run`.
+fn record_reports(year: u16, reports: &[run::DayReport]) -> Result<(), ExitCode> {
+    record_answers(year, |store| reports.iter().map(|r| match &r.result {
+        run::DayResult::Ran { parts, .. } => verify::record(store, r.day, parts),
+        _ => 0,
+    }).sum())
+}
+
+/// Finish off a table of [checks](verify::Check) with its tally, and fail
+/// if any of them did.
+fn check_exit_code(format: Format, tally: &verify::Tally, skipped: &[u8]) -> ExitCode {
+    if format == Format::Table {
+        println!("\n{tally}");
+
+        if !skipped.is_empty() {
+            let list: Vec<String> = skipped.iter().map(u8::to_string).collect();
+            println!("No solver for day(s) {}", list.join(", "));
+        }
+    }
+
+    match tally.ok() {
+        true => ExitCode::SUCCESS,
+        false => ExitCode::from(EXIT_PART_FAILED),
+    }
+}
+
+/// `--year`, or the latest year if it wasn't given.
+fn select_year(registry: &Registry, year: Option<u16>) -> Option<&schema::Year> {
+    let found = match year {
+        Some(y) => registry.year(y),
+        None => registry.latest(),
+    };
+
+    if found.is_none() {
+        eprintln!("[ERROR] No solutions for {}!", year.unwrap_or_default());
+    }
+
+    found
+}
+
+/// The worst thing that happened across a `run`, as an exit code.
+fn run_exit_code(reports: &[run::DayReport]) -> ExitCode {
+    let worst = reports.iter()
+        .flat_map(|r| match &r.result {
+            run::DayResult::NoInput(_) | run::DayResult::BadInput(_) | run::DayResult::Panicked(_) => vec![EXIT_INPUT],
+            run::DayResult::Ran { parts, .. } => parts.iter().filter_map(|(_, o)| match o {
+                Outcome::Solved(_) => None,
+                Outcome::Failed(_) | Outcome::Panicked(_) | Outcome::Killed(_) => Some(EXIT_PART_FAILED),
+                Outcome::Cancelled => Some(EXIT_TIMEOUT),
+            }).collect(),
+        })
+        .min();
+
+    // The codes are numbered most serious first.
+    ExitCode::from(worst.unwrap_or(0))
+}
+
+/// The answer cache, if `--cache` asked for it.
+fn open_cache(args: &Cli) -> Option<cache::Cache> {
+    if !args.cache || args.no_cache {
+        return None;
+    }
+
+    match cache::Cache::open(std::path::Path::new(cache::Cache::DEFAULT_ROOT)) {
+        Ok(c) => Some(c),
+        Err(e) => {
+            eprintln!("[WARN] Running without the cache: {e}");
+            None
+        },
+    }
+}
+
+fn main() -> ExitCode {
     let args = Cli::parse();
+    let cache = open_cache(&args);
+
+    // Writes the trace out when `main` returns.
+    let _trace = match args.trace.map(|f| trace::init(f, args.trace_file.as_deref())) {
+        Some(Err(e)) => {
+            eprintln!("[ERROR] Couldn't start tracing: {e}");
+            return ExitCode::from(EXIT_USAGE);
+        },
+        started => started.and_then(Result::ok),
+    };
+
+    let registry = match Registry::load() {
+        Ok(r) => r,
+        Err(e) => {
+            eprintln!("[ERROR] {e}");
+            return ExitCode::from(EXIT_USAGE);
+        },
+    };
 
     match args.day {
-        Days::Day { day, part } => {
+        Days::Day { day, part, input, example, timeout, isolate, memory_limit, record, format } => {
 
-            let input = {
-                let f = format!("./input/day{}", day);
-                
-                std::fs::read_to_string(f)?
+            let Some(year) = select_year(&registry, args.year) else {
+                return ExitCode::from(EXIT_USAGE);
             };
 
-            let day = day.saturating_sub(1);
+            let Some((day, solution)) = u8::try_from(day).ok().and_then(|d| Some((d, year.get(d)?))) else {
+                eprintln!("[ERROR] {} day {day} not found!", year.number());
+                return ExitCode::from(EXIT_USAGE);
+            };
 
-            let solution = match problem::DAYS.get(day as usize) {
-                Some(p) => p,
-                None => {
-                    eprintln!("[ERROR] {day} not found!");
-                    return Err("Invalid day selected!".into());
+            if solution.is_empty() {
+                eprintln!("[ERROR] {} day {day} has no solution yet!", year.number());
+                return ExitCode::from(EXIT_USAGE);
+            }
+
+            let parts = match part.as_deref().unwrap_or("all").to_lowercase().as_str() {
+                "a"     => &[Part::A][..],
+                "b"     => &[Part::B][..],
+                "all"   => &[Part::A, Part::B][..],
+                p       => {
+                    eprintln!("[ERROR] Invalid part `{p}`; expected a, b or all");
+                    return ExitCode::from(EXIT_USAGE);
                 },
             };
 
-            if solution.is_empty() {
-                return Err("Empty Solution!".into());
+            if let Some(name) = example {
+                let opts = RunOptions { timeout, ..RunOptions::default() };
+
+                return match examples::check(year.number(), day, solution, name.as_deref(), parts, &opts) {
+                    Ok(checks) => {
+                        print_records(format, &checks);
+
+                        match checks.iter().all(|c| c.status == verify::Status::Pass) {
+                            true => ExitCode::SUCCESS,
+                            false => ExitCode::from(EXIT_PART_FAILED),
+                        }
+                    },
+                    Err(e) => {
+                        eprintln!("[ERROR] {e}");
+                        ExitCode::from(EXIT_USAGE)
+                    },
+                };
             }
 
-            let out_a = Arc::new(Mutex::new(schema::Answer::Unimplemented));
-            let out_b = Arc::new(Mutex::new(schema::Answer::Unimplemented));
+            let source = input::Source::from_args(input, args.input_dir);
+
+            if format != Format::Table {
+                let opts = RunOptions { timeout, progress: false, cache: cache.as_ref(), isolate: isolation(isolate, memory_limit) };
+                let report = run::run_day(year, &source, day, solution, parts, &opts);
+                print_records(format, &output::Record::from_report(year.number(), &report));
+
+                if record {
+                    if let Err(code) = record_reports(year.number(), std::slice::from_ref(&report)) {
+                        return code;
+                    }
+                }
+
+                return run_exit_code(&[report]);
+            }
 
             println!("--------------------------------------");
-            println!("Day {}: {}", day + 1, solution.title());
+            println!("{} Day {}: {}", year.number(), day, solution.title());
             println!("--------------------------------------");
 
-            match part.unwrap().to_lowercase().as_str() {
-                "a"     => {
-                                let out_a_clone = out_a.clone();
+            let opts = RunOptions { timeout, progress: true, cache: cache.as_ref(), isolate: isolation(isolate, memory_limit) };
 
-                                let t = std::thread::spawn(move || 
-                                    solution.part_a(&input, out_a_clone)
-                                )
-                                .join()
-                                .expect("Part A Errored");
-                                assert!(t.is_ok());
+            let (outcomes, cached) = match run::run_day(year, &source, day, solution, parts, &opts).result {
+                run::DayResult::NoInput(e) => {
+                    eprintln!("[ERROR] Could not read input: {e}");
+                    return ExitCode::from(EXIT_INPUT);
+                },
+                run::DayResult::BadInput(e) => {
+                    eprintln!("[ERROR] Could not parse input:\n{e}");
+                    return ExitCode::from(EXIT_INPUT);
+                },
+                run::DayResult::Panicked(m) => {
+                    eprintln!("[ERROR] Parsing the input panicked: {m}");
+                    return ExitCode::from(EXIT_INPUT);
+                },
+                run::DayResult::Ran { parse, parts, cached } => {
+                    match parse {
+                        Some(elapsed) => println!("Parsed input (took {elapsed:#?})"),
+                        None if !cached.is_empty() => println!("Every part is cached; didn't parse the input"),
+                        None => {},
+                    }
 
-                                println!("Part A answer: {} (took {:#?})", out_a.lock().unwrap(), t.unwrap());
-                            },
-                "b"     => {
-                                let out_b_clone = out_b.clone();
+                    (parts, cached)
+                },
+            };
+
+            let mut code = ExitCode::SUCCESS;
+
+            for (part, outcome) in outcomes.iter() {
+                match outcome {
+                    Outcome::Solved(s) => print_solution(&part.to_string(), s, cached.contains(part)),
+                    Outcome::Failed(e) => {
+                        println!("Part {part} failed: {e}");
+                        code = ExitCode::from(EXIT_PART_FAILED);
+                    },
+                    Outcome::Panicked(msg) => {
+                        println!("Part {part} panicked: {msg}");
+                        code = ExitCode::from(EXIT_PART_FAILED);
+                    },
+                    Outcome::Killed(why) => {
+                        println!("Part {part} was killed: {why}");
+                        code = ExitCode::from(EXIT_PART_FAILED);
+                    },
+                    Outcome::Cancelled => {
+                        println!("Part {part} cancelled after {:#?}", timeout.unwrap_or_default());
+
+                        if code == ExitCode::SUCCESS {
+                            code = ExitCode::from(EXIT_TIMEOUT);
+                        }
+                    },
+                }
+            }
+
+            if record {
+                if let Err(code) = record_answers(year.number(), |store| verify::record(store, day, &outcomes)) {
+                    return code;
+                }
+            }
+
+            code
+        },
+        Days::Run { days, all: _, parallel, inputs, timeout, isolate, memory_limit, record, format } => {
+            let Some(year) = select_year(&registry, args.year) else {
+                return ExitCode::from(EXIT_USAGE);
+            };
+
+            if let Some(dir) = inputs {
+                let opts = RunOptions { timeout, isolate: isolation(isolate, memory_limit), ..RunOptions::default() };
+
+                let (rows, skipped) = match batch::run_batch(year, &dir, days.as_ref(), &opts) {
+                    Ok(r) => r,
+                    Err(e) => {
+                        eprintln!("[ERROR] {e}");
+                        return ExitCode::from(EXIT_INPUT);
+                    },
+                };
+
+                print_records(format, &rows);
+
+                return check_exit_code(format, &verify::Tally::of(rows.iter().map(|r| &r.check)), &skipped);
+            }
+
+            let source = input::Source::from_args(None, args.input_dir);
+            let opts = RunOptions {
+                timeout,
+                progress: !parallel && format == Format::Table,
+                cache: cache.as_ref(),
+                isolate: isolation(isolate, memory_limit),
+            };
+            let start = Instant::now();
+
+            let (reports, skipped) = run::run_days(year, &source, days.as_ref(), parallel, &opts);
+
+            match format {
+                Format::Table => println!("{}", run::summary(&reports, &skipped, start.elapsed())),
+                f => print_records(f, &reports.iter()
+                                              .flat_map(|r| output::Record::from_report(year.number(), r))
+                                              .collect::<Vec<_>>()),
+            }
+
+            if record {
+                if let Err(code) = record_reports(year.number(), &reports) {
+                    return code;
+                }
+            }
+
+            run_exit_code(&reports)
+        },
+        Days::Verify { days, parallel, timeout, format } => {
+            let Some(year) = select_year(&registry, args.year) else {
+                return ExitCode::from(EXIT_USAGE);
+            };
+
+            let store = match AnswerStore::load(std::path::Path::new(AnswerStore::DEFAULT_ROOT), year.number()) {
+                Ok(s) => s,
+                Err(e) => {
+                    eprintln!("[ERROR] {e}");
+                    return ExitCode::from(EXIT_INPUT);
+                },
+            };
+
+            let source = input::Source::from_args(None, args.input_dir);
+            let opts = RunOptions { timeout, ..RunOptions::default() };
+            let (reports, skipped) = run::run_days(year, &source, days.as_ref(), parallel, &opts);
+
+            let checks: Vec<_> = reports.iter().flat_map(|r| verify::check(year.number(), r, |p| store.get(r.day, p))).collect();
+
+            print_records(format, &checks);
+
+            check_exit_code(format, &verify::Tally::of(&checks), &skipped)
+        },
+        Days::Bench { days, all: _, warmup, runs, save, compare, threshold, baseline, format } => {
+            let Some(year) = select_year(&registry, args.year) else {
+                return ExitCode::from(EXIT_USAGE);
+            };
+
+            let source = input::Source::from_args(None, args.input_dir);
+            let opts = bench::BenchOptions { warmup, runs: runs as usize };
+            let (selected, _) = run::select_days(year, days.as_ref());
+
+            let benches: Vec<_> = selected.iter()
+                                          .map(|&(d, s)| bench::bench_day(year, &source, d, s, &opts))
+                                          .collect();
+
+            let mut stored = match save || compare {
+                true => match baseline::Baseline::load(&baseline) {
+                    Ok(b) => Some(b),
+                    Err(e) => {
+                        eprintln!("[ERROR] {e}");
+                        return ExitCode::from(EXIT_INPUT);
+                    },
+                },
+                false => None,
+            };
+
+            let comparisons = match &stored {
+                Some(b) if compare => b.compare(year.number(), &benches, threshold / 100.0),
+                _ => Vec::new(),
+            };
+
+            match format {
+                Format::Table => {
+                    print!("{}", bench::table(&benches));
+
+                    if compare {
+                        print!("\n{}", baseline::comparison_table(&comparisons));
+                    }
+                },
+                f => print_records(f, &benches.iter()
+                                              .flat_map(|b| output::Record::from_bench(year.number(), b))
+                                              .map(|r| r.with_comparison(&comparisons))
+                                              .collect::<Vec<_>>()),
+            }
 
-                                let t = std::thread::spawn(move || 
-                                    solution.part_b(&input, out_b_clone)
-                                )
-                                .join()
-                                .expect("Part B Errored");
-                                assert!(t.is_ok());
+            let mut code = match benches.iter().any(|b| b.parse.is_err() || b.parts.iter().any(|(_, m)| m.is_err())) {
+                true => ExitCode::from(EXIT_PART_FAILED),
+                false => ExitCode::SUCCESS,
+            };
+
+            if comparisons.iter().any(|c| c.regressed) {
+                eprintln!("[ERROR] Slower than the baseline by more than {threshold}%");
+                code = ExitCode::from(EXIT_REGRESSION);
+            }
+
+            if let Some(stored) = stored.as_mut().filter(|_| save) {
+                stored.record(year.number(), &benches);
+
+                if let Err(e) = stored.save() {
+                    eprintln!("[ERROR] Couldn't save {}: {e}", baseline.display());
+                    return ExitCode::from(EXIT_INPUT);
+                }
+            }
+
+            code
+        },
+        Days::Fetch { days } => {
+            let Some(year) = select_year(&registry, args.year) else {
+                return ExitCode::from(EXIT_USAGE);
+            };
+
+            let client = match client::Config::load(std::path::Path::new(client::Config::PATH)).and_then(|c| c.client()) {
+                Ok(c) => c,
+                Err(e) => {
+                    eprintln!("[ERROR] {e}");
+                    return ExitCode::from(EXIT_USAGE);
+                },
+            };
+
+            let root = args.input_dir.unwrap_or_else(|| PathBuf::from(input::Source::DEFAULT_ROOT));
+            let mut code = ExitCode::SUCCESS;
+
+            for day in days.0 {
+                match client.cached_input(year, day, &root) {
+                    Ok((path, true)) => println!("Day {day}: saved {}", path.display()),
+                    Ok((path, false)) => println!("Day {day}: already have {}", path.display()),
+                    Err(e) => {
+                        eprintln!("[ERROR] Day {day}: {e}");
+                        code = ExitCode::from(EXIT_SERVER);
+                    },
+                }
+            }
 
-                                println!("Part B answer: {} (took {:#?})", out_b.lock().unwrap(), t.unwrap());
+            code
+        },
+        Days::Submit { day, part, answer } => {
+            let Some(year) = select_year(&registry, args.year) else {
+                return ExitCode::from(EXIT_USAGE);
+            };
+
+            let client = match client::Config::load(std::path::Path::new(client::Config::PATH)).and_then(|c| c.client()) {
+                Ok(c) => c,
+                Err(e) => {
+                    eprintln!("[ERROR] {e}");
+                    return ExitCode::from(EXIT_USAGE);
+                },
+            };
+
+            let answer = match answer {
+                Some(a) => a,
+                None => {
+                    let Some(solver) = year.get(day).filter(|s| !s.is_empty()) else {
+                        eprintln!("[ERROR] {} day {day} has no solution yet!", year.number());
+                        return ExitCode::from(EXIT_USAGE);
+                    };
+
+                    let source = input::Source::from_args(None, args.input_dir);
+                    let report = run::run_day(year, &source, day, solver, &[part], &RunOptions { progress: true, cache: cache.as_ref(), ..RunOptions::default() });
+
+                    match report.outcome(part) {
+                        Some(Outcome::Solved(s)) => match &s.answer {
+                            schema::Answer::Unimplemented => {
+                                eprintln!("[ERROR] Day {day} part {part} isn't implemented yet");
+                                return ExitCode::from(EXIT_USAGE);
+                            },
+                            schema::Answer::Grid(g) if g.ocr().is_none() => {
+                                eprintln!("[ERROR] Couldn't read the letters in day {day} part {part}; pass the answer instead");
+                                return ExitCode::from(EXIT_USAGE);
                             },
-                "all"   => std::thread::scope(|s| {
-                                let out_a_clone = out_a.clone();
-                                let out_b_clone = out_b.clone();
+                            answer => answer.to_string(),
+                        },
+                        _ => {
+                            eprintln!("[ERROR] Couldn't solve day {day} part {part} to submit it");
+                            return run_exit_code(&[report]);
+                        },
+                    }
+                },
+            };
 
-                                let a = s.spawn(|| solution.part_a(&input, out_a_clone));
-                                let b = s.spawn(|| solution.part_b(&input, out_b_clone));
+            println!("Submitting {answer} for {} day {day} part {part}", year.number());
 
-                                let d_a = a.join().expect("Part A Thread Died").unwrap();
-                                let d_b = b.join().expect("Part B Thread Died").unwrap();
+            match client.submit(year.number(), day, part, &answer) {
+                Ok(client::Verdict::Correct) => {
+                    println!("Correct!");
 
-                                println!("Part A answer: {} (took {:#?})", out_a.lock().unwrap(), d_a);
-                                println!("Part B answer: {} (took {:#?})", out_b.lock().unwrap(), d_b);
-                            }),
-                _       => return Err("Invalid Part Selection!".into()),
+                    let recorded = record_answers(year.number(), |store| {
+                        usize::from(store.set(day, part, &answer.parse().unwrap_or_else(|e| match e {})))
+                    });
+
+                    match recorded {
+                        Ok(()) => ExitCode::SUCCESS,
+                        Err(code) => code,
+                    }
+                },
+                Ok(v @ client::Verdict::RateLimited(_)) => {
+                    println!("Not checked: {v}");
+                    ExitCode::from(EXIT_SERVER)
+                },
+                Ok(v) => {
+                    println!("Not accepted: {v}");
+                    ExitCode::from(EXIT_PART_FAILED)
+                },
+                Err(e) => {
+                    eprintln!("[ERROR] {e}");
+                    ExitCode::from(EXIT_SERVER)
+                },
+            }
+        },
+        Days::Cache { action: CacheAction::Clear } => {
+            match cache::Cache::clear(std::path::Path::new(cache::Cache::DEFAULT_ROOT)) {
+                Ok(n) => {
+                    println!("Removed {n} cached answer(s)");
+                    ExitCode::SUCCESS
+                },
+                Err(e) => {
+                    eprintln!("[ERROR] Couldn't clear the cache: {e}");
+                    ExitCode::from(EXIT_INPUT)
+                },
             }
+        },
+        Days::Worker { day, part, memory_limit } => {
+            let Some(solver) = select_year(&registry, args.year).and_then(|y| y.get(day)) else {
+                eprintln!("[ERROR] No solver for day {day}");
+                return ExitCode::from(EXIT_USAGE);
+            };
 
+            match isolate::worker(solver, part, &isolate::Limits { memory: memory_limit }) {
+                Ok(()) => ExitCode::SUCCESS,
+                Err(e) => {
+                    eprintln!("[ERROR] {e}");
+                    ExitCode::from(EXIT_USAGE)
+                },
+            }
         },
-        Days::List => unimplemented!(),
+        Days::New { day, title, dir } => {
+            let Some(year) = args.year.or(registry.latest().map(|y| y.number())) else {
+                eprintln!("[ERROR] No year to add the day to; pass --year");
+                return ExitCode::from(EXIT_USAGE);
+            };
+
+            if registry.get(year, day).is_some_and(|s| !s.is_empty()) {
+                eprintln!("[ERROR] {year} day {day} already has a solver");
+                return ExitCode::from(EXIT_USAGE);
+            }
+
+            let Some(dir) = dir.or_else(|| scaffold::find_crate(&std::env::current_dir().ok()?)) else {
+                eprintln!("[ERROR] No `src/problem.rs` here or above to add the day to; pass --dir");
+                return ExitCode::from(EXIT_USAGE);
+            };
+
+            match scaffold::create(&dir, year, day, &title) {
+                Ok(file) => {
+                    println!("Created {}", file.display());
+                    ExitCode::SUCCESS
+                },
+                Err(e) => {
+                    eprintln!("[ERROR] {e}");
+                    ExitCode::from(EXIT_USAGE)
+                },
+            }
+        },
+        Days::List { format } => match list::list(&registry, &input::Source::from_args(None, args.input_dir), args.year, format) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => {
+                eprintln!("[ERROR] {e}");
+                ExitCode::from(EXIT_INPUT)
+            },
+        },
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use clap::Parser;
+
+    use super::Cli;
+
+    #[test]
+    fn years() {
+        assert!(Cli::try_parse_from(["aoc", "--year", "2014", "list"]).is_err());
+        assert!(Cli::try_parse_from(["aoc", "--year", "twenty", "list"]).is_err());
+
+        let cli = Cli::try_parse_from(["aoc", "--year", "2015", "list"]).unwrap();
+        assert_eq!(cli.year, Some(2015));
     }
 
-    Ok(())
+    #[test]
+    fn thresholds() {
+        for bad in ["-5", "NaN", "inf", "ten"] {
+            assert!(Cli::try_parse_from(["aoc", "bench", "--all", &format!("--threshold={bad}")]).is_err(), "{bad}");
+        }
+
+        assert!(Cli::try_parse_from(["aoc", "bench", "--all", "--threshold", "0"]).is_ok());
+        assert!(Cli::try_parse_from(["aoc", "bench", "--all", "--threshold", "2.5"]).is_ok());
+    }
 }
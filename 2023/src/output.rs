@@ -0,0 +1,345 @@
+//! Row-per-item output shared by the commands: aligned tables, JSON, CSV
+//! and Markdown.
+
+use std::time::Duration;
+
+use schema::{alloc::Allocations, BoxError};
+use serde::Serialize;
+
+use crate::{baseline::Comparison, bench::DayBench, run::{DayReport, DayResult}, runner::Outcome, Format};
+
+/// Anything that can be printed one row per item.
+pub trait Tabular: Serialize {
+    const HEADERS: &'static [&'static str];
+
+    fn cells(&self) -> Vec<String>;
+}
+
+/// One phase of one day, the same shape for `day`, `run` and `bench`.
+#[derive(Debug, Serialize)]
+pub struct Record {
+    pub year: u16,
+    pub day: u8,
+    pub title: &'static str,
+    /// `Parse`, `A` or `B`.
+    pub part: String,
+    pub answer: Option<String>,
+    /// See [`schema::Answer::kind`].
+    pub kind: Option<&'static str>,
+    /// For `bench`, the median. For answers from the cache, how long it
+    /// took when it was solved.
+    pub duration_ns: Option<u64>,
+    /// For `bench`, the spread of the timed runs and how many there were.
+    pub min_ns: Option<u64>,
+    pub max_ns: Option<u64>,
+    pub mean_ns: Option<u64>,
+    pub stddev_ns: Option<u64>,
+    pub runs: Option<usize>,
+    /// Whether the answer came from the cache (or, for `Parse`, whether
+    /// parsing was skipped because everything did).
+    pub cached: bool,
+    /// Allocations the part made, if the binary counts them. For `bench`,
+    /// the last run's.
+    pub allocations: Option<u64>,
+    pub allocated_bytes: Option<u64>,
+    /// Most bytes the part held at once.
+    pub peak_bytes: Option<u64>,
+    /// For `bench --compare`, the baseline median for the same input.
+    pub baseline_ns: Option<u64>,
+    /// For `bench --compare`, percent slower than the baseline (negative
+    /// if faster).
+    pub change_pct: Option<f64>,
+    /// For `bench --compare`, whether the change is past `--threshold`.
+    pub regressed: Option<bool>,
+    pub error: Option<String>,
+}
+
+fn ns(d: Duration) -> Option<u64> {
+    Some(d.as_nanos() as u64)
+}
+
+/// `n` bytes, in the largest binary unit that keeps it at least 1.
+pub fn bytes(n: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+
+    if n < 1024 {
+        return format!("{n} B");
+    }
+
+    let (mut size, mut unit) = (n as f64 / 1024.0, 0);
+
+    while size >= 1024.0 && unit + 1 < UNITS.len() {
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    format!("{size:.1} {}", UNITS[unit])
+}
+
+impl Record {
+    fn new(year: u16, day: u8, title: &'static str, part: String) -> Self {
+        Self {
+            year, day, title, part,
+            answer: None, kind: None, duration_ns: None,
+            min_ns: None, max_ns: None, mean_ns: None, stddev_ns: None, runs: None,
+            cached: false,
+            allocations: None, allocated_bytes: None, peak_bytes: None,
+            baseline_ns: None, change_pct: None, regressed: None,
+            error: None,
+        }
+    }
+
+    fn with_allocations(self, a: Option<Allocations>) -> Self {
+        Self {
+            allocations: a.map(|a| a.count),
+            allocated_bytes: a.map(|a| a.bytes),
+            peak_bytes: a.map(|a| a.peak),
+            ..self
+        }
+    }
+
+    /// Fill in how this phase compares to the baseline, if it was compared.
+    pub fn with_comparison(self, comparisons: &[Comparison]) -> Self {
+        let Some(c) = comparisons.iter().find(|c| c.day == self.day && c.phase == self.part) else {
+            return self;
+        };
+
+        Self {
+            baseline_ns: c.before.and_then(ns),
+            change_pct: c.change().map(|ch| ch * 100.0),
+            regressed: Some(c.regressed),
+            ..self
+        }
+    }
+
+    /// A record for parsing, then one for each part that ran.
+    pub fn from_report(year: u16, r: &DayReport) -> Vec<Self> {
+        let record = |part: &str| Self::new(year, r.day, r.title, part.to_string());
+
+        let (parse, parts, cached) = match &r.result {
+            DayResult::NoInput(e) | DayResult::BadInput(e) => {
+                return vec![Self { error: Some(e.clone()), ..record("Parse") }];
+            },
+            DayResult::Panicked(m) => {
+                return vec![Self { error: Some(format!("panicked: {m}")), ..record("Parse") }];
+            },
+            DayResult::Ran { parse, parts, cached } => (*parse, parts, cached),
+        };
+
+        let skipped = parse.is_none() && !cached.is_empty();
+        let mut records = vec![Self { duration_ns: parse.and_then(ns), cached: skipped, ..record("Parse") }];
+
+        for (part, outcome) in parts {
+            let base = Self { cached: r.cached(*part), ..record(&part.to_string()) };
+
+            records.push(match outcome {
+                Outcome::Solved(s) => Self {
+                    answer: Some(s.answer.to_string()),
+                    kind: Some(s.answer.kind()),
+                    duration_ns: ns(s.elapsed),
+                    ..base
+                }.with_allocations(s.allocations),
+                Outcome::Failed(e) => Self { error: Some(e.to_string()), ..base },
+                Outcome::Panicked(m) => Self { error: Some(format!("panicked: {m}")), ..base },
+                Outcome::Cancelled => Self { error: Some("timed out".to_string()), ..base },
+                Outcome::Killed(why) => Self { error: Some(why.clone()), ..base },
+            });
+        }
+
+        records
+    }
+
+    pub fn from_bench(year: u16, b: &DayBench) -> Vec<Self> {
+        std::iter::once(("Parse".to_string(), &b.parse, None))
+            .chain(b.parts.iter().map(|(p, m)| (p.to_string(), m, b.allocations(*p))))
+            .map(|(part, measured, allocations)| {
+                let base = Self::new(year, b.day, b.title, part);
+
+                match measured {
+                    Ok(s) => Self {
+                        duration_ns: ns(s.median),
+                        min_ns: ns(s.min),
+                        max_ns: ns(s.max),
+                        mean_ns: ns(s.mean),
+                        stddev_ns: ns(s.stddev),
+                        runs: Some(s.runs),
+                        ..base
+                    }.with_allocations(allocations),
+                    Err(e) => Self { error: Some(e.clone()), ..base },
+                }
+            })
+            .collect()
+    }
+}
+
+impl Tabular for Record {
+    const HEADERS: &'static [&'static str] = &[
+        "year", "day", "title", "part", "answer", "kind", "duration_ns",
+        "min_ns", "max_ns", "mean_ns", "stddev_ns", "runs", "cached",
+        "allocations", "allocated_bytes", "peak_bytes", "baseline_ns", "change_pct", "regressed", "error",
+    ];
+
+    fn cells(&self) -> Vec<String> {
+        let opt = |s: Option<String>| s.unwrap_or_default();
+
+        vec![
+            self.year.to_string(),
+            self.day.to_string(),
+            self.title.to_string(),
+            self.part.clone(),
+            opt(self.answer.clone()),
+            opt(self.kind.map(str::to_string)),
+            opt(self.duration_ns.map(|n| n.to_string())),
+            opt(self.min_ns.map(|n| n.to_string())),
+            opt(self.max_ns.map(|n| n.to_string())),
+            opt(self.mean_ns.map(|n| n.to_string())),
+            opt(self.stddev_ns.map(|n| n.to_string())),
+            opt(self.runs.map(|n| n.to_string())),
+            self.cached.to_string(),
+            opt(self.allocations.map(|n| n.to_string())),
+            opt(self.allocated_bytes.map(|n| n.to_string())),
+            opt(self.peak_bytes.map(|n| n.to_string())),
+            opt(self.baseline_ns.map(|n| n.to_string())),
+            opt(self.change_pct.map(|p| format!("{p:.1}"))),
+            opt(self.regressed.map(|r| r.to_string())),
+            opt(self.error.clone()),
+        ]
+    }
+}
+
+fn csv_cell(s: &str) -> String {
+    match s.contains([',', '"', '\n', '\r']) {
+        true => format!("\"{}\"", s.replace('"', "\"\"")),
+        false => s.to_string(),
+    }
+}
+
+fn markdown_cell(s: &str) -> String {
+    s.replace('|', "\\|").replace('\n', "<br>")
+}
+
+/// Render `rows` in `format`; [`Format::Table`] is plain aligned columns.
+pub fn render<T: Tabular>(format: Format, rows: &[T]) -> Result<String, BoxError> {
+    let cells: Vec<Vec<String>> = rows.iter().map(T::cells).collect();
+
+    let line = |cells: Vec<String>, sep: &str| cells.join(sep) + "\n";
+    let headers = || T::HEADERS.iter().map(|h| h.to_string());
+
+    let out = match format {
+        Format::Json => serde_json::to_string_pretty(rows)? + "\n",
+        Format::Csv => std::iter::once(headers().collect())
+            .chain(cells)
+            .map(|row: Vec<String>| line(row.iter().map(|c| csv_cell(c)).collect(), ","))
+            .collect(),
+        Format::Markdown => {
+            let rule = vec!["---".to_string(); T::HEADERS.len()];
+
+            [headers().collect(), rule].into_iter()
+                .chain(cells)
+                .map(|row: Vec<String>| format!("| {} |\n", row.iter().map(|c| markdown_cell(c)).collect::<Vec<_>>().join(" | ")))
+                .collect()
+        },
+        Format::Table => {
+            // Only the first line of each cell, so rows stay one line tall.
+            let cells: Vec<Vec<String>> = cells.into_iter()
+                .map(|row| row.iter().map(|c| c.lines().next().unwrap_or_default().to_string()).collect())
+                .collect();
+
+            let widths: Vec<usize> = T::HEADERS.iter().enumerate()
+                .map(|(i, h)| cells.iter().map(|r| r[i].len()).max().unwrap_or(0).max(h.len()))
+                .collect();
+
+            std::iter::once(headers().collect())
+                .chain(cells)
+                .map(|row: Vec<String>| {
+                    let padded: Vec<String> = row.iter().zip(&widths).map(|(c, w)| format!("{c:<w$}")).collect();
+                    line(padded, "  ").trim_end().to_string() + "\n"
+                })
+                .collect()
+        },
+    };
+
+    Ok(out)
+}
+
+#[cfg(test)]
+mod test {
+    use indoc::indoc;
+
+    use schema::alloc::Allocations;
+
+    use std::time::Duration;
+
+    use super::{bytes, render, Record};
+    use crate::{baseline::Comparison, bench::{DayBench, Stats}, runner::Part, Format};
+
+    fn records() -> Vec<Record> {
+        vec![
+            Record { answer: Some("1,2".to_string()), kind: Some("string"), duration_ns: Some(12),
+                     ..Record::new(2023, 1, "Trebuchet?!", "A".to_string()) }
+                .with_allocations(Some(Allocations { count: 3, bytes: 2048, peak: 1024 })),
+            Record { error: Some("said \"no\" | twice".to_string()),
+                     ..Record::new(2023, 1, "Trebuchet?!", "B".to_string()) },
+        ]
+    }
+
+    #[test]
+    fn csv() {
+        assert_eq!(render(Format::Csv, &records()).unwrap(), indoc! {r#"
+            year,day,title,part,answer,kind,duration_ns,min_ns,max_ns,mean_ns,stddev_ns,runs,cached,allocations,allocated_bytes,peak_bytes,baseline_ns,change_pct,regressed,error
+            2023,1,Trebuchet?!,A,"1,2",string,12,,,,,,false,3,2048,1024,,,,
+            2023,1,Trebuchet?!,B,,,,,,,,,false,,,,,,,"said ""no"" | twice"
+        "#});
+    }
+
+    #[test]
+    fn markdown() {
+        assert_eq!(render(Format::Markdown, &records()).unwrap(), indoc! {r#"
+            | year | day | title | part | answer | kind | duration_ns | min_ns | max_ns | mean_ns | stddev_ns | runs | cached | allocations | allocated_bytes | peak_bytes | baseline_ns | change_pct | regressed | error |
+            | --- | --- | --- | --- | --- | --- | --- | --- | --- | --- | --- | --- | --- | --- | --- | --- | --- | --- | --- | --- |
+            | 2023 | 1 | Trebuchet?! | A | 1,2 | string | 12 |  |  |  |  |  | false | 3 | 2048 | 1024 |  |  |  |  |
+            | 2023 | 1 | Trebuchet?! | B |  |  |  |  |  |  |  |  | false |  |  |  |  |  |  | said "no" \| twice |
+        "#});
+    }
+
+    #[test]
+    fn bench_stats() {
+        let us = Duration::from_micros;
+        let stats = Stats::from_samples(&[us(1), us(2), us(6)]).unwrap();
+
+        let bench = DayBench {
+            day: 1, title: "Trebuchet?!", input_hash: None,
+            parse: Ok(stats),
+            parts: vec![(Part::A, Err("boom".to_string()))],
+            allocations: Vec::new(),
+        };
+
+        let [parse, a] = Record::from_bench(2023, &bench).try_into().unwrap();
+
+        assert_eq!((parse.duration_ns, parse.min_ns, parse.max_ns, parse.mean_ns), (Some(2000), Some(1000), Some(6000), Some(3000)));
+        assert_eq!(parse.runs, Some(3));
+        assert!(parse.stddev_ns.is_some());
+        assert_eq!((a.min_ns, a.runs, a.error.as_deref()), (None, None, Some("boom")));
+    }
+
+    #[test]
+    fn compared() {
+        let comparisons = [Comparison { day: 1, phase: "A".to_string(), before: Some(Duration::from_nanos(10)),
+                                        now: Duration::from_nanos(12), regressed: true }];
+
+        let [a, b] = records().try_into().unwrap();
+        let (a, b) = (a.with_comparison(&comparisons), b.with_comparison(&comparisons));
+
+        assert_eq!((a.baseline_ns, a.regressed), (Some(10), Some(true)));
+        assert!(a.change_pct.is_some_and(|p| (p - 20.0).abs() < 1e-9));
+        assert_eq!((b.baseline_ns, b.change_pct, b.regressed), (None, None, None));
+    }
+
+    #[test]
+    fn sizes() {
+        assert_eq!(bytes(0), "0 B");
+        assert_eq!(bytes(1023), "1023 B");
+        assert_eq!(bytes(1536), "1.5 KiB");
+        assert_eq!(bytes(5 << 20), "5.0 MiB");
+    }
+}
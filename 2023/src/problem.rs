@@ -1,4 +1,5 @@
-use schema::Solver;
+//! Each day registers itself with `#[solver(year = .., day = ..)]`; the
+//! modules just need to be compiled in.
 
 mod day1;
 mod day2;
@@ -20,25 +21,18 @@ mod day17;
 mod day18;
 mod day19;
 
-pub const DAYS: &[&dyn Solver] = &[
-    &day1::Day01,
-    &day2::Day02,
-    &day3::Day03,
-    &day4::Day04,
-    &day5::Day05,
-    &day6::Day06,
-    &day7::Day07,
-    &day8::Day08,
-    &day9::Day09,
-    &day10::Day10,
-    &day11::Day11,
-    &day12::Day12,
-    &day13::Day13,
-    &day14::Day14,
-    &day15::Day15,
-    &day16::Day16,
-    &day17::Day17,
-    &day18::Day18,
-    &day19::Day19,
-];
+#[cfg(test)]
+mod test {
+    use schema::registry::Registry;
 
+    const YEAR: u16 = 2023;
+
+    #[test]
+    fn registered() {
+        let registry = Registry::load().unwrap();
+
+        // Not an exact match, so a day just made with `new` doesn't fail it.
+        assert!((1..=19).all(|d| registry.get(YEAR, d).is_some_and(|s| !s.is_empty())));
+        assert_eq!(registry.get(YEAR, 6).unwrap().title(), "Wait For It");
+    }
+}
@@ -1,9 +1,9 @@
-use std::time::{Duration, Instant};
+use indoc::indoc;
+use schema::{Answer, BoxError, Context, Example, solver, Solver};
 
-use schema::Solver;
 
-
-pub struct Day01;
+#[solver(year = 2023, day = 1)]
+pub(crate) struct Day01;
 
 const DIGITS: [&str; 10] = [
     "zero", "one", "two", "three", "four", 
@@ -37,41 +37,63 @@ fn naive_replace(line: &mut str, digit: &str, val: &str) {
 
 }
 
+const CASE_A: &str = indoc! {"
+    1abc2
+    pqr3stu8vwx
+    a1b2c3d4e5f
+    treb7uchet
+"};
+
+const CASE_B: &str = indoc! {"
+    two1nine
+    eightwothree
+    abcone2threexyz
+    xtwone3four
+    4nineeightseven2
+    zoneight234
+    7pqrstsixteen
+"};
+
 impl Solver for Day01 {
+    type Input<'a> = Vec<&'a str>;
 
     fn title(&self) -> &'static str {
         r"Trebuchet?!"
     }
-    
-    /// Part 1: Finds the first and last numerical value in a string,
-    /// converts it to a valid two digit number, and sums all values for the
-    /// `input` file and stores in `out`.
-    fn part_a(&self, input: &str, out: std::sync::Arc<std::sync::Mutex<schema::Answer>>) 
-            -> Result<Duration, Box<(dyn std::error::Error + Send + Sync)>> {
+
+    fn examples(&self) -> &'static [Example] {
+        const {
+            &[
+                Example::new("case_a", CASE_A).part_a("142"),
+                Example::new("case_b", CASE_B).part_b("281"),
+            ]
+        }
+    }
+
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Input<'a>, BoxError> {
+        Ok(input.lines().collect())
+    }
+
+    /// Part 1: Finds the first and last digit on each line, reads them as a
+    /// two digit number, and sums those over every line.
+    fn part_a(&self, input: &Self::Input<'_>, _ctx: &Context) -> Result<Answer, BoxError> {
         
         let mut total = 0;
 
-        let ts = Instant::now();
-        for l in input.lines() {
+        for l in input.iter() {
             total += get_num(l)
         }
-        let ts = ts.elapsed();
-
-        let mut output = out.lock().unwrap();
-        *output = total.into();
 
-        Ok(ts)
+        Ok(total.into())
     }
 
-    /// Part 2: ...
-    fn part_b(&self, input: &str, out: std::sync::Arc<std::sync::Mutex<schema::Answer>>) 
-            -> Result<Duration, Box<(dyn std::error::Error + Send + Sync)>> {
+    /// Part 2: As part 1, but digits spelled out as words (`one` to `nine`)
+    /// count too.
+    fn part_b(&self, input: &Self::Input<'_>, _ctx: &Context) -> Result<Answer, BoxError> {
 
         let mut total = 0;
 
-        let ts = Instant::now();
-
-        for l in input.lines() {
+        for l in input.iter() {
             let mut mod_line: String = l.to_string();
 
             for (i, digit) in DIGITS.iter().enumerate() {
@@ -80,60 +102,28 @@ impl Solver for Day01 {
 
             total += get_num(mod_line.as_str())
         }
-        let ts = ts.elapsed();
-
-        let mut output = out.lock().unwrap();
-        *output = total.into();
 
-        Ok(ts)
-    }
-
-    fn is_empty(&self) -> bool {
-        false
+        Ok(total.into())
     }
 
 }
 
 #[cfg(test)]
 mod test {
+    use schema::*;
 
-    use std::{sync::{Arc, Mutex}, ops::Deref};
-
-    use schema::Solver;
-    use indoc::indoc;
-
-    use super::Day01;
-
-    const CASE_A: &str = indoc! {"
-        1abc2
-        pqr3stu8vwx
-        a1b2c3d4e5f
-        treb7uchet
-    "};
-
-    const CASE_B: &str = indoc! {"
-        two1nine
-        eightwothree
-        abcone2threexyz
-        xtwone3four
-        4nineeightseven2
-        zoneight234
-        7pqrstsixteen
-    "};
+    use super::{Day01, CASE_A, CASE_B};
 
     #[test]
     fn part_a () {
-        let out = Arc::new(Mutex::new(schema::Answer::Unimplemented));
-        let _ = Day01.part_a(CASE_A, out.clone());
-        assert_eq!(*out.lock().unwrap().deref(), schema::Answer::Number(142));
+        let input = Day01.parse(CASE_A).unwrap();
+        assert_eq!(Day01.part_a(&input, &Context::default()).unwrap(), Answer::Number(142));
     }
 
     #[test]
     fn part_b () {
-        let out = Arc::new(Mutex::new(schema::Answer::Unimplemented));
-        let _ = Day01.part_b(CASE_B, out.clone());
-        assert_eq!(*out.lock().unwrap().deref(), schema::Answer::Number(281));
+        let input = Day01.parse(CASE_B).unwrap();
+        assert_eq!(Day01.part_b(&input, &Context::default()).unwrap(), Answer::Number(281));
     }
 
-
 }
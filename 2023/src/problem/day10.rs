@@ -1,9 +1,9 @@
 use std::{
-    time::{Duration, Instant}, 
     collections::{BTreeMap, VecDeque}
 };
 
-use schema::Solver;
+use indoc::indoc;
+use schema::{parse, Answer, BoxError, Cancelled, Context, Example, solver, Solver};
 
 const START_DIR: [((i32, i32), Direction); 4] = 
     [((0, -1), Direction::N), ((-1, 0), Direction::W), ((0, 1), Direction::S), ((1, 0), Direction::E)];
@@ -15,25 +15,17 @@ struct Stream {
 }
 
 #[derive(Debug)]
-struct PipeMap {
-    map: [[Pipe; 140]; 140],
+pub(crate) struct PipeMap {
+    rows: Vec<Vec<Pipe>>,
     start: Pos,
 }
 
 impl PipeMap {
-    fn new() -> Self {
-        Self { map: [[Pipe::Ground; 140]; 140], start: (0, 0) }
-    }
-
-    fn tile(&mut self, xy: Pos, typ: char) {
-
-        let t = Pipe::parse(typ);
-
-        if t == Pipe::Start {
-            self.start = xy;
-        }
-
-        self.map[xy.0 as usize][xy.1 as usize] = t;
+    fn pipe(&self, (x, y): Pos) -> Pipe {
+        self.rows.get(y as usize)
+                 .and_then(|row| row.get(x as usize))
+                 .copied()
+                 .unwrap_or(Pipe::Ground)
     }
 
     fn next(&self, cur: (Pos, u8)) -> (Pos, u8) {
@@ -48,7 +40,7 @@ impl PipeMap {
             Direction::S => {(cur_pos.0, cur_pos.1+1)},
         };
 
-        let pipe = self.map[next_pos.0 as usize][next_pos.1 as usize];
+        let pipe = self.pipe(next_pos);
 
         if pipe == Pipe::Start {
             return (self.start, facing as u8);
@@ -66,12 +58,12 @@ impl PipeMap {
         for (uv, direction) in START_DIR.iter() {
             let (x, y) = (self.start.0 as i32 + uv.0, self.start.1 as i32 + uv.1);
 
-            if x < 0 || y < 0 || x >= 140 || y >= 140 {
+            if x < 0 || y < 0 {
                 continue;
             }
 
             let facing = *direction as u8;
-            let pipe = self.map[x as usize][y as usize];
+            let pipe = self.pipe((x as u32, y as u32));
 
             if pipe == Pipe::Ground {
                 continue;
@@ -92,7 +84,7 @@ impl PipeMap {
         Stream { depth: 1, nodes: dir }
     }
 
-    fn path(&self) -> Path {
+    fn path(&self, ctx: &Context) -> Result<Path, Cancelled> {
         let mut path = Path::new();
 
         let mut stream = self.start_paths();
@@ -102,7 +94,8 @@ impl PipeMap {
         let mut tock = 1u8;
 
         while let Some(node) = stream.nodes.pop_front() {
-            
+            ctx.check()?;
+
             let next_node = self.next(node);
 
             if path.values().any(|v| v.contains(&next_node)) {
@@ -128,7 +121,7 @@ impl PipeMap {
 
         }
 
-        path
+        Ok(path)
     }
 
     fn area(&self, path: Path) -> i32 {
@@ -155,11 +148,6 @@ impl PipeMap {
 
 }
 
-impl Default for PipeMap {
-    fn default() -> Self {
-        Self::new()
-    }
-}
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum Pipe {
@@ -175,17 +163,17 @@ enum Pipe {
 
 impl Pipe {
     
-    fn parse(input: char) -> Self {
+    fn parse(input: char) -> Option<Self> {
         match input {
-            '|' => Self::Vertical,
-            '-' => Self::Horizontal,
-            'L' => Self::NE,
-            'J' => Self::NW,
-            '7' => Self::SW,
-            'F' => Self::SE,
-            'S' => Self::Start,
-            '.' => Self::Ground,
-            _ => unreachable!()
+            '|' => Some(Self::Vertical),
+            '-' => Some(Self::Horizontal),
+            'L' => Some(Self::NE),
+            'J' => Some(Self::NW),
+            '7' => Some(Self::SW),
+            'F' => Some(Self::SE),
+            'S' => Some(Self::Start),
+            '.' => Some(Self::Ground),
+            _ => None
         }
     }
 
@@ -234,111 +222,133 @@ impl Direction {
 type Path = BTreeMap<u32, Vec<(Pos, u8)>>;
 type Pos = (u32, u32);
 
-pub struct Day10;
-
+#[solver(year = 2023, day = 10)]
+pub(crate) struct Day10;
+
+
+const MAP: &str = indoc! {"
+    ..F7.
+    .FJ|.
+    SJ.L7
+    |F--J
+    LJ...
+"};
+
+const MAP2: &str = indoc! {"
+    FF7FSF7F7F7F7F7F---7
+    L|LJ||||||||||||F--J
+    FL-7LJLJ||||||LJL-77
+    F--JF--7||LJLJ7F7FJ-
+    L---JF-JLJ.||-FJLJJ7
+    |F|F-JF---7F7-L7L|7|
+    |FFJF7L7F-JF7|JL---7
+    7-L-JL7||F7|L7F-7F7|
+    L.L7LFJ|||||FJL7||LJ
+    L7JLJL-JLJLJL--JLJ.L
+"};
 
 impl Solver for Day10 {
+    type Input<'a> = PipeMap;
+
     fn title(&self) -> &'static str {
         r"Pipe Maze"
     }
 
-    fn part_a(&self, input: &str, out: std::sync::Arc<std::sync::Mutex<schema::Answer>>) 
-            -> Result<Duration, Box<(dyn std::error::Error + Send + Sync)>> {
-        
-        let ts = Instant::now();
-        let mut map = PipeMap::default();
-        for (y, line) in input.lines().enumerate() {
-            for (x, c) in line.chars().enumerate() {
-                map.tile((x as u32, y as u32), c);
-            }
+    fn examples(&self) -> &'static [Example] {
+        const {
+            &[
+                Example::new("map", MAP).part_a("8"),
+                Example::new("map2", MAP2).part_b("10"),
+            ]
         }
+    }
 
-        let path = map.path();
-
-        let max_dist = *path.keys().max().unwrap() / 2;
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Input<'a>, BoxError> {
+        let mut rows: Vec<Vec<Pipe>> = Vec::new();
+        let mut start = None;
 
-        let d = ts.elapsed();
+        for line in parse::lines(input) {
+            let y = line.number - 1;
+            let mut row = Vec::with_capacity(line.text.len());
 
-        let mut output = out.lock().unwrap();
-        *output = max_dist.into();
+            for (x, c) in line.text.chars().enumerate() {
+                let pipe = Pipe::parse(c)
+                    .ok_or_else(|| line.error_at(x, "one of `|-LJ7FS.`"))?;
 
-        Ok(d)
+                if pipe == Pipe::Start {
+                    start = Some((x as u32, y as u32));
+                }
 
-    }
+                row.push(pipe);
+            }
 
-    fn part_b(&self, input: &str, out: std::sync::Arc<std::sync::Mutex<schema::Answer>>) 
-            -> Result<Duration, Box<(dyn std::error::Error + Send + Sync)>> {
-        
-        let ts = Instant::now();
-        let mut map = PipeMap::default();
-        for (y, line) in input.lines().enumerate() {
-            for (x, c) in line.chars().enumerate() {
-                map.tile((x as u32, y as u32), c);
+            // Every row has to be as wide as the first.
+            if let Some(width) = rows.first().map(Vec::len) {
+                if row.len() > width {
+                    return Err(line.error_at(width, "the end of the row").into());
+                }
+                if row.len() < width {
+                    return Err(line.error_eol(format_args!("{width} tiles")).into());
+                }
             }
+
+            rows.push(row);
         }
 
-        let path = map.path();
+        let start = start.ok_or("no `S` tile in the map")?;
 
-        let area = map.area(path);
+        Ok(PipeMap { rows, start })
+    }
 
-        let d = ts.elapsed();
+    fn part_a(&self, map: &Self::Input<'_>, ctx: &Context) -> Result<Answer, BoxError> {
+        
+        let path = map.path(ctx)?;
+
+        let max_dist = *path.keys().max().unwrap() / 2;
 
-        let mut output = out.lock().unwrap();
-        *output = area.into();
+        Ok(max_dist.into())
 
-        Ok(d)
-        
     }
 
-    fn is_empty(&self) -> bool {
-        false
+    fn part_b(&self, map: &Self::Input<'_>, ctx: &Context) -> Result<Answer, BoxError> {
+        
+        let path = map.path(ctx)?;
+
+        let area = map.area(path);
+
+        Ok(area.into())
+        
     }
 
 }
 
 #[cfg(test)]
 mod test {
-    use std::{sync::Arc, sync::Mutex, ops::Deref};
-
     use schema::*;
 
-    use indoc::indoc;
-
-    use super::Day10;
-
-    const MAP: &str = indoc! {"
-        ..F7.
-        .FJ|.
-        SJ.L7
-        |F--J
-        LJ...
-    "};
-
-    const MAP2: &str = indoc! {"
-        FF7FSF7F7F7F7F7F---7
-        L|LJ||||||||||||F--J
-        FL-7LJLJ||||||LJL-77
-        F--JF--7||LJLJ7F7FJ-
-        L---JF-JLJ.||-FJLJJ7
-        |F|F-JF---7F7-L7L|7|
-        |FFJF7L7F-JF7|JL---7
-        7-L-JL7||F7|L7F-7F7|
-        L.L7LFJ|||||FJL7||LJ
-        L7JLJL-JLJLJL--JLJ.L
-    "};
+    use super::{Day10, MAP, MAP2};
 
     #[test]
     fn part_a() {
-        let out = Arc::new(Mutex::new(Answer::Unimplemented));
-        let _ = Day10.part_a(MAP, out.clone());
-        assert_eq!(*out.lock().unwrap().deref(), Answer::Number(8));
+        let input = Day10.parse(MAP).unwrap();
+        assert_eq!(Day10.part_a(&input, &Context::default()).unwrap(), Answer::Number(8));
     }
 
     #[test]
     fn part_b() {
-        let out = Arc::new(Mutex::new(Answer::Unimplemented));
-        let _ = Day10.part_b(MAP2, out.clone());
-        assert_eq!(*out.lock().unwrap().deref(), Answer::Number(10));
+        let input = Day10.parse(MAP2).unwrap();
+        assert_eq!(Day10.part_b(&input, &Context::default()).unwrap(), Answer::Number(10));
+    }
+
+    #[test]
+    fn ragged_rows() {
+        let err = Day10.parse("S-7\n|.|.\nL-J").err().unwrap();
+        let err = err.downcast::<SolveError>().unwrap();
+        assert_eq!((err.line, err.column), (2, 4));
+
+        let err = Day10.parse("S-7\n|\nL-J").err().unwrap();
+        let err = err.downcast::<SolveError>().unwrap();
+        assert_eq!((err.line, err.column), (2, 2));
     }
 
 }
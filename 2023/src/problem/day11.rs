@@ -1,13 +1,12 @@
-use std::time::{Duration, Instant};
 use vob::{vob, Vob};
 use itertools::Itertools;
 
-use schema::Solver;
+use schema::{Answer, BoxError, Context, solver, Solver};
 
 type Pos = (usize, usize);
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
-struct Galaxy {
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct Galaxy {
     position: Pos,
 }
 
@@ -26,7 +25,7 @@ fn distance(a: [usize;2], b: [usize;2]) -> usize {
 type Map = Vec<Galaxy>;
 
 #[derive(Debug)]
-struct Universe {
+pub(crate) struct Universe {
     x: Vob,
     y: Vob
 }
@@ -52,18 +51,17 @@ impl Universe {
 }
 
 
-pub struct Day11;
+#[solver(year = 2023, day = 11)]
+pub(crate) struct Day11;
 
 impl Solver for Day11 {
+    type Input<'a> = (Universe, Map);
     
     fn title(&self) -> &'static str {
         r"Cosmic Expansion"
     }
 
-    fn part_a(&self, input: &str, out: std::sync::Arc<std::sync::Mutex<schema::Answer>>) 
-            -> Result<Duration, Box<(dyn std::error::Error + Send + Sync)>> {
-        
-        let ts = Instant::now();
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Input<'a>, BoxError> {
         let lines = input.lines().collect::<Vec<_>>();
 
         let mut mx = vob![false; lines[0].len()];
@@ -82,7 +80,12 @@ impl Solver for Day11 {
             }
         }
 
-        let universe = Universe { x: mx, y: my };
+        Ok((Universe { x: mx, y: my }, map))
+    }
+
+    fn part_a(&self, (universe, map): &Self::Input<'_>, _ctx: &Context) -> Result<Answer, BoxError> {
+        
+        let mut map = map.clone();
 
         universe.hubble_expansion(2, &mut map);
 
@@ -92,38 +95,13 @@ impl Solver for Day11 {
                              .map(|(g1, g2)| distance(g1, g2))
                              .sum();
 
-        let d = ts.elapsed();
-
-        let mut output = out.lock().unwrap();
-        *output = dist.into();
-
-        Ok(d)
+        Ok(dist.into())
 
     }
 
-    fn part_b(&self, input: &str, out: std::sync::Arc<std::sync::Mutex<schema::Answer>>) 
-            -> Result<Duration, Box<(dyn std::error::Error + Send + Sync)>> {
-
-        let ts = Instant::now();
-        let lines = input.lines().collect::<Vec<_>>();
-
-        let mut mx = vob![false; lines[0].len()];
-        let mut my = vob![false; lines.len()];
-
-        let mut map: Map = Vec::new();
-
-        for (y, l) in lines.iter().enumerate() {
-            for (x, c) in l.bytes().enumerate() {
-                if c == b'#' {
-                    map.push(Galaxy { position: (x, y) });
-
-                    mx.set(x, true);
-                    my.set(y, true);
-                }
-            }
-        }
+    fn part_b(&self, (universe, map): &Self::Input<'_>, _ctx: &Context) -> Result<Answer, BoxError> {
 
-        let universe = Universe { x: mx, y: my };
+        let mut map = map.clone();
 
         universe.hubble_expansion(1_000_000, &mut map);
 
@@ -133,15 +111,6 @@ impl Solver for Day11 {
                              .map(|(g1, g2)| distance(g1, g2))
                              .sum();
 
-        let d = ts.elapsed();
-
-        let mut output = out.lock().unwrap();
-        *output = dist.into();
-
-        Ok(d)
-    }
-
-    fn is_empty(&self) -> bool {
-        false
+        Ok(dist.into())
     }
 }
@@ -1,13 +1,13 @@
-use std::time::{Duration, Instant};
 
+use indoc::indoc;
+use schema::{parse, Answer, BoxError, Context, Example, solver, Solver};
 
-use schema::Solver;
 
-
-pub struct Day12;
+#[solver(year = 2023, day = 12)]
+pub(crate) struct Day12;
 
 /// Heavily inspired by <https://github.com/maneatingape/advent-of-code-rust/blob/main/src/year2023/day12.rs>
-fn solve(springs: &Vec<u8>, rep: usize, count: &Vec<usize>) -> usize {
+fn solve(springs: &[u8], rep: usize, count: &[usize]) -> usize {
     
     let mut spring_pat = Vec::with_capacity(springs.len() * rep + 1);
     let mut spring_count = Vec::with_capacity(count.len() * rep);
@@ -86,116 +86,98 @@ fn solve(springs: &Vec<u8>, rep: usize, count: &Vec<usize>) -> usize {
 }
 
 
+const SPRINGS: &str = indoc! {"
+    ???.### 1,1,3
+    .??..??...?##. 1,1,3
+    ?#?#?#?#?#?#?#? 1,3,1,6
+    ????.#...#... 4,1,1
+    ????.######..#####. 1,6,5
+    ?###???????? 3,2,1
+"};
+
 impl Solver for Day12 {
+    type Input<'a> = Vec<(Vec<u8>, Vec<usize>)>;
+
     fn title(&self) -> &'static str {
         r"Hot Springs"
     }
 
-    fn part_a(&self, input: &str, out: std::sync::Arc<std::sync::Mutex<schema::Answer>>) 
-            -> Result<Duration, Box<(dyn std::error::Error + Send + Sync)>> {
-        
-        let ts = Instant::now();
-        let mut total = 0;
-
-        let mut pattern: Vec<usize> = Vec::new();
-        let mut spring_pat: Vec<u8> = Vec::new();
+    fn examples(&self) -> &'static [Example] {
+        const { &[Example::new("springs", SPRINGS).part_a("21").part_b("525152")] }
+    }
 
-        for line in input.lines() {
-            if let Some((springs, arrangement)) = line.split_once(' ') {
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Input<'a>, BoxError> {
+        let mut records = Vec::new();
 
-                pattern.clear();
-                pattern = arrangement.split(',').map(|v| v.parse().unwrap()).collect();
+        for line in parse::lines(input) {
+            if let Some((springs, arrangement)) = line.text.split_once(' ') {
 
-                spring_pat.clear();
-                spring_pat = springs.bytes().collect();
+                let pattern: Vec<usize> = arrangement.split(',')
+                                                     .map(|v| line.parse(v, "a group size"))
+                                                     .collect::<Result<_, _>>()?;
 
-                let temp = solve(&spring_pat, 1, &pattern);
+                let spring_pat: Vec<u8> = springs.bytes().collect();
 
-                total += temp;
+                records.push((spring_pat, pattern));
 
             }
         }
 
-        let d = ts.elapsed();
-
-        let mut output = out.lock().unwrap();
-        *output = total.into();
-
-        Ok(d)
-
+        Ok(records)
     }
 
-    fn part_b(&self, input: &str, out: std::sync::Arc<std::sync::Mutex<schema::Answer>>) 
-            -> Result<Duration, Box<(dyn std::error::Error + Send + Sync)>> {
+    fn part_a(&self, records: &Self::Input<'_>, _ctx: &Context) -> Result<Answer, BoxError> {
         
-        let ts = Instant::now();
         let mut total = 0;
 
-        let mut pattern: Vec<usize> = Vec::new();
-        let mut spring_pat: Vec<u8> = Vec::new();
-
-        for line in input.lines() {
-            if let Some((springs, arrangement)) = line.split_once(' ') {
-
-                pattern.clear();
-                pattern = arrangement.split(',').map(|v| v.parse().unwrap()).collect();
+        for (spring_pat, pattern) in records.iter() {
+            total += solve(spring_pat, 1, pattern);
+        }
 
-                spring_pat.clear();
-                spring_pat = springs.bytes().collect();
+        Ok(total.into())
 
-                let temp = solve(&spring_pat, 5, &pattern);
+    }
 
-                total += temp;
+    fn part_b(&self, records: &Self::Input<'_>, ctx: &Context) -> Result<Answer, BoxError> {
+        
+        let mut total = 0;
 
-            }
+        for (i, (spring_pat, pattern)) in records.iter().enumerate() {
+            ctx.check()?;
+            total += solve(spring_pat, 5, pattern);
+            ctx.progress(i as u64 + 1, records.len() as u64);
         }
 
-        let d = ts.elapsed();
-
-        let mut output = out.lock().unwrap();
-        *output = total.into();
-
-        Ok(d)
+        Ok(total.into())
 
     }
-
-    fn is_empty(&self) -> bool {
-        false
-    }
 }
 
 #[cfg(test)]
 mod test {
-
-    use std::{sync::Arc, sync::Mutex, ops::Deref};
-
     use schema::*;
 
-    use indoc::indoc;
-
-    use super::Day12;
-
-    const SPRINGS: &str = indoc! {"
-        ???.### 1,1,3
-        .??..??...?##. 1,1,3
-        ?#?#?#?#?#?#?#? 1,3,1,6
-        ????.#...#... 4,1,1
-        ????.######..#####. 1,6,5
-        ?###???????? 3,2,1
-    "};
+    use super::{Day12, SPRINGS};
 
     #[test]
     fn part_a() {
-        let out = Arc::new(Mutex::new(Answer::Unimplemented));
-        let _ = Day12.part_a(SPRINGS, out.clone());
-        assert_eq!(*out.lock().unwrap().deref(), Answer::Number(21));
+        let input = Day12.parse(SPRINGS).unwrap();
+        assert_eq!(Day12.part_a(&input, &Context::default()).unwrap(), Answer::Number(21));
     }
 
     #[test]
     fn part_b() {
-        let out = Arc::new(Mutex::new(Answer::Unimplemented));
-        let _ = Day12.part_b(SPRINGS, out.clone());
-        assert_eq!(*out.lock().unwrap().deref(), Answer::Number(525152));
+        let input = Day12.parse(SPRINGS).unwrap();
+        assert_eq!(Day12.part_b(&input, &Context::default()).unwrap(), Answer::Number(525152));
+    }
+
+    #[test]
+    fn bad_group() {
+        let err = Day12.parse("???.### 1,1,3\n.??..??...?##. 1,one,3").err().unwrap();
+        let err = err.downcast::<SolveError>().unwrap();
+
+        assert_eq!((err.line, err.column), (2, 18));
+        assert_eq!(err.found, "one");
     }
 
 }
@@ -1,7 +1,7 @@
-use std::time::{Duration, Instant};
 use vob::Vob;
 
-use schema::Solver;
+use indoc::indoc;
+use schema::{Answer, BoxError, Context, Example, solver, Solver};
 
 type Mirror = Vec<Vob>;
 
@@ -116,103 +116,83 @@ impl Reflections {
 
 }
 
-pub struct Day13;
+#[solver(year = 2023, day = 13)]
+pub(crate) struct Day13;
 
 
+const MIRROR: &str = indoc! {"
+    #.##..##.
+    ..#.##.#.
+    ##......#
+    ##......#
+    ..#.##.#.
+    ..##..##.
+    #.#.##.#.
+
+    #...##..#
+    #....#..#
+    ..##..###
+    #####.##.
+    #####.##.
+    ..##..###
+    #....#..#
+"};
+
 impl Solver for Day13 {
+    type Input<'a> = Vec<Mirror>;
+
     fn title(&self) -> &'static str {
         r"Point of Incidence"
     }
 
-    fn part_a(&self, input: &str, out: std::sync::Arc<std::sync::Mutex<schema::Answer>>) 
-            -> Result<Duration, Box<(dyn std::error::Error + Send + Sync)>> {
-        
-        let ts = Instant::now();
+    fn examples(&self) -> &'static [Example] {
+        const { &[Example::new("mirror", MIRROR).part_a("405").part_b("400")] }
+    }
 
-        let mirrors = input.split("\n\n");
-        let total: usize =  mirrors.map( |mirror| {
-                                            let p = parse_mirror(mirror);
-                                            Reflections::default().horizontal_reflect(&p, 0)
-                                                                  .vertical_reflect(&p, 0)
-                                                                  .calculate()
-                                        }).sum();
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Input<'a>, BoxError> {
+        Ok(input.split("\n\n").map(parse_mirror).collect())
+    }
 
-        let d = ts.elapsed();
+    fn part_a(&self, mirrors: &Self::Input<'_>, _ctx: &Context) -> Result<Answer, BoxError> {
         
-        let mut output = out.lock().unwrap();
-        *output = total.into();
+        let total: usize =  mirrors.iter().map( |p| {
+                                            Reflections::default().horizontal_reflect(p, 0)
+                                                                  .vertical_reflect(p, 0)
+                                                                  .calculate()
+                                        }).sum();
 
-        Ok(d)
+        Ok(total.into())
 
     }
 
-    fn part_b(&self, input: &str, out: std::sync::Arc<std::sync::Mutex<schema::Answer>>) 
-            -> Result<Duration, Box<(dyn std::error::Error + Send + Sync)>> {
+    fn part_b(&self, mirrors: &Self::Input<'_>, _ctx: &Context) -> Result<Answer, BoxError> {
         
-        let ts = Instant::now();
-
-        let mirrors = input.split("\n\n");
-        let total: usize =  mirrors.map( |mirror| {
-                                            let p = parse_mirror(mirror);
-                                            Reflections::default().horizontal_reflect(&p, 1)
-                                                                  .vertical_reflect(&p, 1)
+        let total: usize =  mirrors.iter().map( |p| {
+                                            Reflections::default().horizontal_reflect(p, 1)
+                                                                  .vertical_reflect(p, 1)
                                                                   .calculate()
                                         }).sum();
 
-        let d = ts.elapsed();
-        
-        let mut output = out.lock().unwrap();
-        *output = total.into();
-
-        Ok(d)
-
-    }
+        Ok(total.into())
 
-    fn is_empty(&self) -> bool {
-        false
     }
 }
 
 #[cfg(test)]
 mod test {
-    
-    use std::{sync::Arc, sync::Mutex, ops::Deref};
-
     use schema::*;
 
-    use indoc::indoc;
-
-    use super::Day13;
-
-    const MIRROR: &str = indoc! {"
-        #.##..##.
-        ..#.##.#.
-        ##......#
-        ##......#
-        ..#.##.#.
-        ..##..##.
-        #.#.##.#.
-
-        #...##..#
-        #....#..#
-        ..##..###
-        #####.##.
-        #####.##.
-        ..##..###
-        #....#..#
-    "};
+    use super::{Day13, MIRROR};
 
     #[test]
     fn part_a() {
-        let out = Arc::new(Mutex::new(Answer::Unimplemented));
-        let _ = Day13.part_a(MIRROR, out.clone());
-        assert_eq!(*out.lock().unwrap().deref(), Answer::Number(405));
+        let input = Day13.parse(MIRROR).unwrap();
+        assert_eq!(Day13.part_a(&input, &Context::default()).unwrap(), Answer::Number(405));
     }
 
     #[test]
     fn part_b() {
-        let out = Arc::new(Mutex::new(Answer::Unimplemented));
-        let _ = Day13.part_b(MIRROR, out.clone());
-        assert_eq!(*out.lock().unwrap().deref(), Answer::Number(400));
+        let input = Day13.parse(MIRROR).unwrap();
+        assert_eq!(Day13.part_b(&input, &Context::default()).unwrap(), Answer::Number(400));
     }
 }
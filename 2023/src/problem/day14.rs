@@ -1,13 +1,12 @@
-use std::time::{Duration, Instant};
-
-use schema::Solver;
+use indoc::indoc;
+use schema::{Answer, BoxError, Context, Example, solver, Solver};
 
 
 type Dish = Vec<Vec<u8>>;
 type Patterns = Vec<Dish>;
 
-#[derive(Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
-struct TiltBox {
+#[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct TiltBox {
     dish: Dish,
 }
 
@@ -135,19 +134,35 @@ impl TiltBox {
     }
 }
 
-pub struct Day14;
+#[solver(year = 2023, day = 14)]
+pub(crate) struct Day14;
+
 
+const DISH: &str = indoc! {"
+    O....#....
+    O.OO#....#
+    .....##...
+    OO.#O....O
+    .O.....O#.
+    O.#..O.#.#
+    ..O..#O..O
+    .......O..
+    #....###..
+    #OO..#....
+"};
 
 impl Solver for Day14 {
+    type Input<'a> = TiltBox;
+
     fn title(&self) -> &'static str {
         r"Parabolic Reflector Dish"
     }
 
-    fn part_a(&self, input: &str, out: std::sync::Arc<std::sync::Mutex<schema::Answer>>) 
-            -> Result<Duration, Box<(dyn std::error::Error + Send + Sync)>> {
-        
-        let ts = Instant::now();
+    fn examples(&self) -> &'static [Example] {
+        const { &[Example::new("dish", DISH).part_a("136").part_b("64")] }
+    }
 
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Input<'a>, BoxError> {
         let mut tiltbox = TiltBox::default();
 
         for l in input.trim().lines() {
@@ -155,40 +170,36 @@ impl Solver for Day14 {
             tiltbox.dish.push(li);
         }
 
-        tiltbox.tilt(Direction::North);
+        Ok(tiltbox)
+    }
 
-        let load = tiltbox.load();
+    fn part_a(&self, input: &Self::Input<'_>, _ctx: &Context) -> Result<Answer, BoxError> {
+        
+        let mut tiltbox = input.clone();
 
-        let d = ts.elapsed();
+        tiltbox.tilt(Direction::North);
 
-        let mut output = out.lock().unwrap();
-        *output = load.into();
+        let load = tiltbox.load();
 
-        Ok(d)
+        Ok(load.into())
     }
 
-    fn part_b(&self, input: &str, out: std::sync::Arc<std::sync::Mutex<schema::Answer>>) 
-            -> Result<Duration, Box<(dyn std::error::Error + Send + Sync)>> {
+    fn part_b(&self, input: &Self::Input<'_>, ctx: &Context) -> Result<Answer, BoxError> {
         
-        let ts = Instant::now();
-
-        let mut tiltbox = TiltBox::default();
+        let mut tiltbox = input.clone();
         let mut pattern = Patterns::new();
 
-        for l in input.trim().lines() {
-            let li = l.bytes().collect::<Vec<u8>>();
-            tiltbox.dish.push(li);
-        }
-
         pattern.push(tiltbox.dish.clone());
 
         for _ in 0..1_000_000_000 {
+            ctx.check()?;
 
             tiltbox.cycle();
 
             if pattern.contains(&tiltbox.dish) {
                 let idx = pattern.iter().position(|d| *d == tiltbox.dish).unwrap();
                 let cycles = pattern.len() - idx;
+                ctx.note(format!("Dish repeats every {cycles} cycles from cycle {idx}"));
                 let closed_cycle = idx + (1_000_000_000 - idx) % cycles;
                 tiltbox.dish = pattern[closed_cycle].clone();
                 break;
@@ -199,55 +210,33 @@ impl Solver for Day14 {
 
         let load = tiltbox.load();
 
-        let d = ts.elapsed();
-
-        let mut output = out.lock().unwrap();
-        *output = load.into();
-
-        Ok(d)
-    }
-
-    fn is_empty(&self) -> bool {
-        false
+        Ok(load.into())
     }
 }
 
 #[cfg(test)]
 mod test {
-    
-    use std::{sync::Arc, sync::Mutex, ops::Deref};
-
     use schema::*;
 
-    use indoc::indoc;
-
-    use super::Day14;
-
-    const DISH: &str = indoc! {"
-        O....#....
-        O.OO#....#
-        .....##...
-        OO.#O....O
-        .O.....O#.
-        O.#..O.#.#
-        ..O..#O..O
-        .......O..
-        #....###..
-        #OO..#....
-    "};
+    use super::{Day14, DISH};
 
     #[test]
     fn part_a() {
-        let out = Arc::new(Mutex::new(Answer::Unimplemented));
-        let _ = Day14.part_a(DISH, out.clone());
-        assert_eq!(*out.lock().unwrap().deref(), Answer::Number(136));
+        let input = Day14.parse(DISH).unwrap();
+        assert_eq!(Day14.part_a(&input, &Context::default()).unwrap(), Answer::Number(136));
     }
 
     #[test]
     fn part_b() {
-        let out = Arc::new(Mutex::new(Answer::Unimplemented));
-        let _ = Day14.part_b(DISH, out.clone());
-        assert_eq!(*out.lock().unwrap().deref(), Answer::Number(64));
+        let input = Day14.parse(DISH).unwrap();
+        assert_eq!(Day14.part_b(&input, &Context::default()).unwrap(), Answer::Number(64));
     }
 
+    #[test]
+    fn cycle_note() {
+        let parsed = Day14.prepare(DISH).unwrap();
+        let solution = parsed.part_b(&Context::default()).unwrap();
+
+        assert_eq!(solution.diagnostics, vec!["Dish repeats every 7 cycles from cycle 3"]);
+    }
 }
@@ -1,60 +1,87 @@
-use std::time::{Duration, Instant};
 
-
-use schema::Solver;
+use indoc::indoc;
+use schema::{parse, Answer, BoxError, Context, Example, solver, Solver};
 
 fn hash(input: &str) -> u32 {
     input.as_bytes().iter().fold(0, |a, &c| ((a + c as u32) * 17) % 256)
 }
 
-pub struct Day15;
+#[derive(Debug)]
+enum Op {
+    Insert(u8),
+    Remove,
+}
+
+#[derive(Debug)]
+pub(crate) struct Step<'a> {
+    text: &'a str,
+    label: &'a str,
+    op: Op,
+}
+
+#[solver(year = 2023, day = 15)]
+pub(crate) struct Day15;
 
+const CASE: &str = indoc! {"
+    rn=1,cm-,qp=3,cm=2,qp-,pc=4,ot=9,ab=5,pc-,pc=6,ot=7
+"};
 
 impl Solver for Day15 {
+    type Input<'a> = Vec<Step<'a>>;
+
     fn title(&self) -> &'static str {
         r"Lens Library"
     }
 
-    fn part_a(&self, input: &str, out: std::sync::Arc<std::sync::Mutex<schema::Answer>>) 
-            -> Result<Duration, Box<(dyn std::error::Error + Send + Sync)>> {
+    fn examples(&self) -> &'static [Example] {
+        const { &[Example::new("case", CASE).part_a("1320").part_b("145")] }
+    }
 
-        let ts = Instant::now();
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Input<'a>, BoxError> {
+        let mut steps = Vec::new();
 
-        let hashes = input.trim().split(',').collect::<Vec<_>>();
+        for line in parse::lines(input).filter(|l| !l.text.is_empty()) {
+            for text in line.text.split(',') {
+                let el = text.find(['=', '-'])
+                             .ok_or_else(|| line.error_on(text, "a step like `rn=1` or `cm-`"))?;
+                let (label, r) = text.split_at(el);
+                let (f, num) = r.split_at(1);
 
-        let total: u32 = hashes.iter().map(|h| hash(h)).sum();
-        
-        let d = ts.elapsed();
+                let op = match f {
+                    "=" => Op::Insert(line.parse(num, "a focal length")?),
+                    _   => Op::Remove,
+                };
 
-        let mut output = out.lock().unwrap();
-        *output = total.into();
+                steps.push(Step { text, label, op });
+            }
+        }
 
-        Ok(d)
+        Ok(steps)
     }
 
-    fn part_b(&self, input: &str, out: std::sync::Arc<std::sync::Mutex<schema::Answer>>) 
-            -> Result<Duration, Box<(dyn std::error::Error + Send + Sync)>> {
+    fn part_a(&self, hashes: &Self::Input<'_>, _ctx: &Context) -> Result<Answer, BoxError> {
 
-        let ts = Instant::now();
+        let total: u32 = hashes.iter().map(|h| hash(h.text)).sum();
         
-        let lenses = input.trim().split(',').collect::<Vec<_>>();
+        Ok(total.into())
+    }
+
+    fn part_b(&self, lenses: &Self::Input<'_>, _ctx: &Context) -> Result<Answer, BoxError> {
 
         const INNER: Vec<(String, u8)> = vec![];
         let mut boxes = vec![INNER; 256];
 
-        lenses.iter().for_each(|&lens| {
-            let el = lens.find(|c| c == '=' || c == '-').unwrap();
-            let (name, r) = lens.split_at(el);
-            let (f, num) = r.split_at(1);
+        lenses.iter().for_each(|lens| {
+            let name = lens.label;
 
             let hash = hash(name);
 
             let lens_box = &mut boxes[hash as usize];
-            if f == "=" {
+            if let Op::Insert(num) = lens.op {
                 if let Some(lens_pos) = lens_box.iter().position(|l| l.0 == name) {
-                    lens_box[lens_pos] = (name.to_string(), num.parse::<u8>().unwrap());
+                    lens_box[lens_pos] = (name.to_string(), num);
                 } else {
-                    lens_box.push((name.to_string(), num.parse::<u8>().unwrap()));
+                    lens_box.push((name.to_string(), num));
                 }
             }
             else {
@@ -67,15 +94,36 @@ impl Solver for Day15 {
             l.iter().enumerate().map(|(b_idx, (_, l))| (b_idx + 1) * *l as usize * (idx + 1)).sum::<usize>() + a
         );
 
-        let d = ts.elapsed();
+        Ok(total.into())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use schema::*;
+
+    use super::{Day15, CASE};
 
-        let mut output = out.lock().unwrap();
-        *output = total.into();
+    #[test]
+    fn part_a() {
+        let input = Day15.parse(CASE).unwrap();
+        assert_eq!(Day15.part_a(&input, &Context::default()).unwrap(), Answer::Number(1320));
+    }
 
-        Ok(d)
+    #[test]
+    fn part_b() {
+        let input = Day15.parse(CASE).unwrap();
+        assert_eq!(Day15.part_b(&input, &Context::default()).unwrap(), Answer::Number(145));
     }
 
-    fn is_empty(&self) -> bool {
-        false
+    #[test]
+    fn bad_step() {
+        let err = Day15.parse("rn=1,cm,qp=x").err().unwrap();
+        let err = err.downcast::<SolveError>().unwrap();
+        assert_eq!((err.line, err.column, err.found.as_str()), (1, 6, "cm"));
+
+        let err = Day15.parse("rn=1,qp=x").err().unwrap();
+        let err = err.downcast::<SolveError>().unwrap();
+        assert_eq!(err.column, 9);
     }
 }
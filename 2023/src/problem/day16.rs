@@ -1,10 +1,10 @@
 use std::{
-    time::{Duration, Instant}, 
     collections::VecDeque, 
     fmt::Display, cmp::max
 };
 use ahash::{HashSet, HashSetExt};
-use schema::Solver;
+use indoc::indoc;
+use schema::{parse, Answer, BoxError, Context, Example, solver, Solver};
 
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -45,8 +45,9 @@ impl Direction {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
-enum NodeTyp {
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum NodeTyp {
+    #[default]
     Empty,
     MirrorDown,
     MirrorUp,
@@ -54,21 +55,15 @@ enum NodeTyp {
     SplitLR,
 }
 
-impl Default for NodeTyp {
-    fn default() -> Self {
-        Self::Empty
-    }
-}
-
 impl NodeTyp {
-    fn parse(c: u8) -> Self {
+    fn parse(c: u8) -> Option<Self> {
         match c {
-            b'.'  => Self::Empty,
-            b'|'  => Self::SplitTD,
-            b'-'  => Self::SplitLR,
-            b'\\' => Self::MirrorDown,
-            b'/'  => Self::MirrorUp,
-            _     => unreachable!()
+            b'.'  => Some(Self::Empty),
+            b'|'  => Some(Self::SplitTD),
+            b'-'  => Some(Self::SplitLR),
+            b'\\' => Some(Self::MirrorDown),
+            b'/'  => Some(Self::MirrorUp),
+            _     => None
         }
     }
 
@@ -215,30 +210,76 @@ type Pos = (i32, i32);
 type Beam = HashSet<Pos>;
 type LaserGrid = Vec<Vec<NodeTyp>>;
 
-pub struct Day16;
+#[solver(year = 2023, day = 16)]
+pub(crate) struct Day16;
+
 
+const GRID: &str = indoc! {"
+    .|...\\....
+    |.-.\\.....
+    .....|-...
+    ........|.
+    ..........
+    .........\\
+    ..../.\\\\..
+    .-.-/..|..
+    .|....-|.\\
+    ..//.|....
+"};
 
 impl Solver for Day16 {
+    type Input<'a> = LaserGrid;
+
     fn title(&self) -> &'static str {
         r"The Floor Will Be Lava"
     }
 
-    fn part_a(&self, input: &str, out: std::sync::Arc<std::sync::Mutex<schema::Answer>>) 
-            -> Result<Duration, Box<(dyn std::error::Error + Send + Sync)>> {
-        let ts = Instant::now();
+    fn examples(&self) -> &'static [Example] {
+        const { &[Example::new("grid", GRID).part_a("46").part_b("51")] }
+    }
 
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Input<'a>, BoxError> {
         let mut grid = LaserGrid::default();
-        let mut beam = Beam::default();
 
         let mut temp = Vec::<NodeTyp>::new();
 
-        for l in input.lines() {
+        for l in parse::lines(input) {
             temp.clear();
-            temp.extend(l.bytes().map(NodeTyp::parse));
+
+            for (x, c) in l.text.chars().enumerate() {
+                let node = u8::try_from(c).ok()
+                    .and_then(NodeTyp::parse)
+                    .ok_or_else(|| l.error_at(x, r"one of `.|-\/`"))?;
+
+                temp.push(node);
+            }
+
+            // Part 2 fires in from every edge, so the grid has to be a rectangle.
+            match grid.first().map(Vec::len) {
+                None if temp.is_empty() => {
+                    return Err(l.error_eol(r"one of `.|-\/`").into());
+                },
+                Some(width) if temp.len() > width => {
+                    return Err(l.error_at(width, "the end of the row").into());
+                },
+                Some(width) if temp.len() < width => {
+                    return Err(l.error_eol(format_args!("{width} tiles")).into());
+                },
+                _ => {}
+            }
+
             grid.push(temp.clone());
         }
 
-        drop(temp);
+        if grid.is_empty() {
+            return Err("the grid is empty".into());
+        }
+
+        Ok(grid)
+    }
+
+    fn part_a(&self, grid: &Self::Input<'_>, _ctx: &Context) -> Result<Answer, BoxError> {
+        let mut beam = Beam::default();
 
         let mut beam_path = BeamPath::default();
 
@@ -246,7 +287,7 @@ impl Solver for Day16 {
         beam.insert((0, 0));
 
         // FIRE!!!
-        beam_path.fire_laser(&grid, &mut beam);
+        beam_path.fire_laser(grid, &mut beam);
 
         // for (y, l) in grid.iter().enumerate() {
         //     for (x, n) in l.iter().enumerate() {
@@ -261,138 +302,65 @@ impl Solver for Day16 {
         
         let total = beam.len();
         
-        let d = ts.elapsed();
-
-        let mut output = out.lock().unwrap();
-        *output = total.into();
-
-        Ok(d)
+        Ok(total.into())
     }
 
-    fn part_b(&self, input: &str, out: std::sync::Arc<std::sync::Mutex<schema::Answer>>) 
-            -> Result<Duration, Box<(dyn std::error::Error + Send + Sync)>> {
-
-        let ts = Instant::now();
-
-        let mut grid = LaserGrid::default();
-
-        let mut temp = Vec::<NodeTyp>::new();
-
-        for l in input.lines() {
-            temp.clear();
-            temp.extend(l.bytes().map(NodeTyp::parse));
-            grid.push(temp.clone());
-        }
-
-        drop(temp);
-
-        let t_max = (0..grid[0].len()).map(|x| {
-                        let mut beam = Beam::default();
-
-                        let mut beam_path = BeamPath::default();
-
-                        beam_path.add_node((0, x as i32), Direction::S);
-                        beam.insert((0, x as i32));
-
-                        // FIRE!!!
-                        beam_path.fire_laser(&grid, &mut beam);
-
-                        beam.len()
-                    }).max().unwrap();
-        let d_max = (0..grid[0].len()).map(|x| {
-                        let mut beam = Beam::default();
-
-                        let mut beam_path = BeamPath::default();
-
-                        beam_path.add_node((grid.len() as i32 - 1, x as i32), Direction::N);
-                        beam.insert((grid.len()as i32 - 1, x as i32));
-
-                        // FIRE!!!
-                        beam_path.fire_laser(&grid, &mut beam);
-
-                        beam.len()
-                    }).max().unwrap();
-
-        let td_max = max(t_max, d_max);
-
-        let l_max = (0..grid.len()).map(|y| {
-                        let mut beam = Beam::default();
+    fn part_b(&self, grid: &Self::Input<'_>, ctx: &Context) -> Result<Answer, BoxError> {
 
-                        let mut beam_path = BeamPath::default();
+        let (h, w) = (grid.len() as i32, grid[0].len() as i32);
 
-                        beam_path.add_node((y as i32, 0), Direction::E);
-                        beam.insert((y as i32, 0));
+        // Every edge tile, facing into the grid.
+        let starts = (0..w).flat_map(|x| [((0, x), Direction::S), ((h - 1, x), Direction::N)])
+            .chain((0..h).flat_map(|y| [((y, 0), Direction::E), ((y, w - 1), Direction::W)]));
 
-                        // FIRE!!!
-                        beam_path.fire_laser(&grid, &mut beam);
+        let mut best = 0;
 
-                        beam.len()
-                    }).max().unwrap();
-        let r_max = (0..grid.len()).map(|y| {
-                        let mut beam = Beam::default();
+        for (pos, direction) in starts {
+            ctx.check()?;
 
-                        let mut beam_path = BeamPath::default();
+            let mut beam = Beam::default();
 
-                        beam_path.add_node((y as i32, grid[0].len() as i32 - 1), Direction::W);
-                        beam.insert((y as i32, grid[0].len() as i32 - 1));
+            let mut beam_path = BeamPath::default();
 
-                        // FIRE!!!
-                        beam_path.fire_laser(&grid, &mut beam);
-
-                        beam.len()
-                    }).max().unwrap();
-
-        let lr_max = max(l_max, r_max);
-
-        let d = ts.elapsed();
+            beam_path.add_node(pos, direction);
+            beam.insert(pos);
 
-        let mut output = out.lock().unwrap();
-        *output = max(td_max, lr_max).into();
+            // FIRE!!!
+            beam_path.fire_laser(grid, &mut beam);
 
-        Ok(d)
-    }
+            best = max(best, beam.len());
+        }
 
-    fn is_empty(&self) -> bool {
-        false
+        Ok(best.into())
     }
 }
 
 
 #[cfg(test)]
 mod test {
-    use std::{sync::Arc, sync::Mutex};
-
     use schema::*;
 
-    use indoc::indoc;
-
-    use super::Day16;
-
-    const GRID: &str = indoc! {"
-        .|...\\....
-        |.-.\\.....
-        .....|-...
-        ........|.
-        ..........
-        .........\\
-        ..../.\\\\..
-        .-.-/..|..
-        .|....-|.\\
-        ..//.|....
-    "};
+    use super::{Day16, GRID};
 
     #[test]
     fn part_a() {
-        let out = Arc::new(Mutex::new(Answer::Unimplemented));
-        let _ = Day16.part_a(GRID, out.clone());
-        assert_eq!(*out.lock().unwrap(), Answer::Number(46));
+        let input = Day16.parse(GRID).unwrap();
+        assert_eq!(Day16.part_a(&input, &Context::default()).unwrap(), Answer::Number(46));
     }
 
     #[test]
     fn part_b() {
-        let out = Arc::new(Mutex::new(Answer::Unimplemented));
-        let _ = Day16.part_b(GRID, out.clone());
-        assert_eq!(*out.lock().unwrap(), Answer::Number(10));
+        let input = Day16.parse(GRID).unwrap();
+        assert_eq!(Day16.part_b(&input, &Context::default()).unwrap(), Answer::Number(51));
+    }
+
+    #[test]
+    fn empty_grid() {
+        assert!(Day16.parse("").is_err());
+
+        let err = Day16.parse("\n.|.").err().unwrap();
+        let err = err.downcast::<SolveError>().unwrap();
+        assert_eq!((err.line, err.column), (1, 1));
     }
 
 }
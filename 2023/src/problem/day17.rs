@@ -1,14 +1,16 @@
-use std::{time::{Duration, Instant}, hash::Hash};
+use std::hash::Hash;
 use ahash::{HashMap, HashMapExt};
 use orx_priority_queue::*;
 
 
-use schema::Solver;
+use indoc::indoc;
+use schema::{parse, Answer, BoxError, Cancelled, Context, Example, solver, Solver};
 
 
-#[derive(Debug, Clone, Copy, PartialOrd, Ord, PartialEq, Eq, Hash)]
+#[derive(Debug, Default, Clone, Copy, PartialOrd, Ord, PartialEq, Eq, Hash)]
 enum Direction {
     N = 0b0001,
+    #[default]
     E = 0b0010,
     W = 0b0100,
     S = 0b1000
@@ -34,12 +36,6 @@ impl Direction {
     }
 }
 
-impl Default for Direction {
-    fn default() -> Self {
-        Self::E
-    }
-}
-
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 struct Node {
     pos: Pos,
@@ -91,12 +87,9 @@ impl Walker {
                         false => 1
                     };
 
-                    match dist <= MAX {
-                        true => {
-                            out.push((Node {pos: p, direction: Some(*direction), steps: dist}, 
-                                            puzzle[p.0 as usize][p.1 as usize] as usize ))
-                        },
-                        false => ()
+                    if dist <= MAX {
+                        out.push((Node {pos: p, direction: Some(*direction), steps: dist}, 
+                                        puzzle[p.0 as usize][p.1 as usize] as usize ))
                     }
                 }
             } else if *direction == Direction::S || *direction == Direction::E {
@@ -127,8 +120,10 @@ impl Walker {
 
     }
 
+    #[tracing::instrument(level = "debug", skip_all, fields(min = MIN, max = MAX))]
     fn walk<const MIN: usize, const MAX: usize>(&mut self, 
-                                                mut unvisited: impl PriorityQueueDecKey<Node, usize>, puzzle: &Puzzle) 
+                                                mut unvisited: impl PriorityQueueDecKey<Node, usize>, puzzle: &Puzzle,
+                                                ctx: &Context) -> Result<(), Cancelled>
     {
 
         let mut seen: HashMap<Node, usize> = HashMap::new();
@@ -139,6 +134,7 @@ impl Walker {
         unvisited.push(Node {pos: self.start, direction: None, steps: 0}, 0);
 
         while let Some((node, g)) = unvisited.pop() {
+            ctx.check()?;
 
             if node.pos == self.goal {
                 self.total_loss = g;
@@ -158,7 +154,7 @@ impl Walker {
                     let next_pos = next_node.pos;
                     let next_loss = g_t + puzzle[next_pos.0 as usize][next_pos.1 as usize] as usize;
                     
-                    if seen.get(&next_node).map_or(true, |&g_n| next_loss < g_n) {
+                    if seen.get(&next_node).is_none_or(|&g_n| next_loss < g_n) {
                         seen.insert(next_node, next_loss);
                         unvisited.try_decrease_key_or_push(&next_node, next_loss);
                     }
@@ -167,6 +163,7 @@ impl Walker {
 
         }
 
+        Ok(())
     }
 
 }
@@ -174,52 +171,67 @@ impl Walker {
 type Puzzle = Vec<Vec<u8>>;
 type Pos = (i32, i32);
 
-pub struct Day17;
-
+#[solver(year = 2023, day = 17)]
+pub(crate) struct Day17;
+
+
+const PUZZLE: &str = indoc! {"
+    2413432311323
+    3215453535623
+    3255245654254
+    3446585845452
+    4546657867536
+    1438598798454
+    4457876987766
+    3637877979653
+    4654967986887
+    4564679986453
+    1224686865563
+    2546548887735
+    4322674655533
+"};
 
 impl Solver for Day17 {
+    type Input<'a> = Puzzle;
+
     fn title(&self) -> &'static str {
         r"Clumsy Crucible"
     }
 
-    fn part_a(&self, input: &str, out: std::sync::Arc<std::sync::Mutex<schema::Answer>>) 
-            -> Result<Duration, Box<(dyn std::error::Error + Send + Sync)>> {
-        
-        let ts = Instant::now();
+    fn examples(&self) -> &'static [Example] {
+        const { &[Example::new("puzzle", PUZZLE).part_a("102").part_b("94")] }
+    }
 
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Input<'a>, BoxError> {
         let mut puzzle = Puzzle::default();
 
-        for line in input.lines() {
-            puzzle.push(line.chars().map(|c| c.to_digit(10).unwrap() as u8).collect::<Vec<u8>>());
+        for line in parse::lines(input) {
+            let row = line.text.chars()
+                               .enumerate()
+                               .map(|(x, c)| c.to_digit(10).map(|d| d as u8).ok_or_else(|| line.error_at(x, "a digit")))
+                               .collect::<Result<Vec<u8>, _>>()?;
+
+            puzzle.push(row);
         }
 
+        Ok(puzzle)
+    }
+
+    fn part_a(&self, puzzle: &Self::Input<'_>, ctx: &Context) -> Result<Answer, BoxError> {
+        
         let unvisited: QuaternaryHeapWithMap<Node, usize> = QuaternaryHeapWithMap::default();
 
         let mut walker = Walker::default();
         walker.set_pos((0, 0));
         walker.set_goal((puzzle.len() as i32 - 1, puzzle[0].len() as i32 - 1));
 
-        walker.walk::<1, 3>(unvisited, &puzzle);
-
-        let d = ts.elapsed();
+        walker.walk::<1, 3>(unvisited, puzzle, ctx)?;
 
-        let mut output = out.lock().unwrap();
-        *output = walker.total_loss.into();
-
-        Ok(d)
+        Ok(walker.total_loss.into())
 
     }
 
-    fn part_b(&self, input: &str, out: std::sync::Arc<std::sync::Mutex<schema::Answer>>) 
-            -> Result<Duration, Box<(dyn std::error::Error + Send + Sync)>> {
-
-        let ts = Instant::now();
-
-        let mut puzzle = Puzzle::default();
-
-        for line in input.lines() {
-            puzzle.push(line.chars().map(|c| c.to_digit(10).unwrap() as u8).collect::<Vec<u8>>());
-        }
+    fn part_b(&self, puzzle: &Self::Input<'_>, ctx: &Context) -> Result<Answer, BoxError> {
 
         let unvisited: QuaternaryHeapWithMap<Node, usize> = QuaternaryHeapWithMap::default();
 
@@ -227,60 +239,47 @@ impl Solver for Day17 {
         walker.set_pos((0, 0));
         walker.set_goal((puzzle.len() as i32 - 1, puzzle[0].len() as i32 - 1));
 
-        walker.walk::<4, 10>(unvisited, &puzzle);
-
-        let d = ts.elapsed();
+        walker.walk::<4, 10>(unvisited, puzzle, ctx)?;
 
-        let mut output = out.lock().unwrap();
-        *output = walker.total_loss.into();
+        Ok(walker.total_loss.into())
 
-        Ok(d)
-
-    }
-
-    fn is_empty(&self) -> bool {
-        false
     }
 }
 
 #[cfg(test)]
 mod test {
-    use std::{sync::Arc, sync::Mutex};
-
     use schema::*;
 
-    use indoc::indoc;
-
-    use super::Day17;
-
-    const PUZZLE: &str = indoc! {"
-        2413432311323
-        3215453535623
-        3255245654254
-        3446585845452
-        4546657867536
-        1438598798454
-        4457876987766
-        3637877979653
-        4654967986887
-        4564679986453
-        1224686865563
-        2546548887735
-        4322674655533
-    "};
+    use super::{Day17, PUZZLE};
 
     #[test]
     fn part_a() {
-        let out = Arc::new(Mutex::new(Answer::Unimplemented));
-        let _ = Day17.part_a(PUZZLE, out.clone());
-        assert_eq!(*out.lock().unwrap(), Answer::Number(102));
+        let input = Day17.parse(PUZZLE).unwrap();
+        assert_eq!(Day17.part_a(&input, &Context::default()).unwrap(), Answer::Number(102));
     }
 
     #[test]
     fn part_b() {
-        let out = Arc::new(Mutex::new(Answer::Unimplemented));
-        let _ = Day17.part_b(PUZZLE, out.clone());
-        assert_eq!(*out.lock().unwrap(), Answer::Number(94));
+        let input = Day17.parse(PUZZLE).unwrap();
+        assert_eq!(Day17.part_b(&input, &Context::default()).unwrap(), Answer::Number(94));
     }
 
+    #[test]
+    fn cancelled() {
+        let input = Day17.parse(PUZZLE).unwrap();
+        let token = CancelToken::default();
+        token.cancel();
+
+        let err = Day17.part_a(&input, &Context::new(token)).unwrap_err();
+        assert!(err.is::<Cancelled>());
+    }
+
+    #[test]
+    fn bad_digit() {
+        let err = Day17.parse("241\n12x").err().unwrap();
+        let err = err.downcast::<SolveError>().unwrap();
+
+        assert_eq!((err.line, err.column), (2, 3));
+        assert_eq!(err.found, "x");
+    }
 }
@@ -1,7 +1,6 @@
-use std::time::{Duration, Instant};
 
-
-use schema::Solver;
+use indoc::indoc;
+use schema::{parse::{self, Line}, Answer, BoxError, Context, Example, SolveError, solver, Solver};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum Direction {
@@ -11,19 +10,17 @@ enum Direction {
     L(u32)
 }
 
-impl From<[u8; 2]> for Direction {
-    fn from(value: [u8; 2]) -> Self {
-        match value[0] {
-            b'U' => Self::U(value[1].into()),
-            b'D' => Self::D(value[1].into()),
-            b'R' => Self::R(value[1].into()),
-            b'L' => Self::L(value[1].into()),
-            _    => unreachable!()
+impl Direction {
+    fn parse(dir: &str, n: u32) -> Option<Self> {
+        match dir {
+            "U" => Some(Self::U(n)),
+            "D" => Some(Self::D(n)),
+            "R" => Some(Self::R(n)),
+            "L" => Some(Self::L(n)),
+            _   => None
         }
     }
-}
 
-impl Direction {
     fn unwrap(&self) -> u32 {
         match self {
             Self::U(n) => *n,
@@ -35,24 +32,28 @@ impl Direction {
 }
 
 #[derive(Debug, Clone, Copy)]
-struct PlanItem {
+pub(crate) struct PlanItem {
     direction: Direction,
     coords: MapRange,
 }
 
 impl PlanItem {
-    fn build(input: &str, is_radix: bool) -> Self {
-        let t = input.split_whitespace().collect::<Vec<_>>();
+    fn build(line: Line<'_>, is_radix: bool) -> Result<Self, SolveError> {
+        let mut t = line.text.split_whitespace();
 
-        let (dir, c) = t.split_at(2);
-        let mut direction = Direction::from([dir[0].as_bytes()[0], dir[1].parse::<u8>().expect("Bad value")]);
-        let color: &str = c.first().unwrap().trim_matches(|c| c == '(' || c == ')');
+        let dir = line.next(&mut t, "a direction")?;
+        let n = line.next(&mut t, "a distance")?;
+        let n = line.parse::<u8>(n, "a distance")?;
+        let mut direction = Direction::parse(dir, n.into())
+            .ok_or_else(|| line.error_on(dir, "one of `U`, `D`, `L` or `R`"))?;
+        let color: &str = line.next(&mut t, "a colour")?
+                              .trim_matches(|c| c == '(' || c == ')');
 
         if is_radix {
-            direction = Self::radix_coords(color);
+            direction = Self::radix_coords(line, color)?;
         }
 
-        Self { direction, coords: ((0, 0), (0, 0)) }
+        Ok(Self { direction, coords: ((0, 0), (0, 0)) })
     }
 
     #[allow(unused_assignments)]
@@ -98,19 +99,22 @@ impl PlanItem {
 
     }
 
-    fn radix_coords(color: &str) -> Direction {
-        let tmp = color.strip_prefix("#").unwrap();
+    fn radix_coords(line: Line<'_>, color: &str) -> Result<Direction, SolveError> {
+        let tmp = color.strip_prefix("#")
+                       .filter(|c| c.len() == 6 && c.is_char_boundary(5))
+                       .ok_or_else(|| line.error_on(color, "a colour like `#70c710`"))?;
         
         let (ctmp, cdir) = tmp.split_at(5);
 
-        let num = u32::from_str_radix(ctmp, 16).unwrap();
+        let num = u32::from_str_radix(ctmp, 16)
+            .map_err(|_| line.error_on(ctmp, "a hex distance"))?;
 
         match cdir {
-            "0" => Direction::R(num),
-            "1" => Direction::D(num),
-            "2" => Direction::L(num),
-            "3" => Direction::U(num),
-            _   => unreachable!()
+            "0" => Ok(Direction::R(num)),
+            "1" => Ok(Direction::D(num)),
+            "2" => Ok(Direction::L(num)),
+            "3" => Ok(Direction::U(num)),
+            _   => Err(line.error_on(cdir, "a direction digit `0`-`3`"))
         }
 
     }
@@ -170,7 +174,7 @@ impl Map {
 
         let area = (trail / 2).abs();
 
-        (area as isize + (self.circum / 2) + 1).abs() as usize
+        (area + (self.circum / 2) + 1).unsigned_abs()
     }
 
     #[inline(always)]
@@ -180,130 +184,110 @@ impl Map {
 
 }
 
+/// Walk the plan, tracing the trench and returning the area it holds.
+fn dig(instrs: &mut [PlanItem]) -> usize {
+    let map_range = map_limits(instrs);
+    let mut map = Map::new(map_range, instrs.len());
 
-pub struct Day18;
-
-
-impl Solver for Day18 {
-    fn title(&self) -> &'static str {
-        r"Lavaduct Lagoon"
-    }
-
-    fn part_a(&self, input: &str, out: std::sync::Arc<std::sync::Mutex<schema::Answer>>) 
-            -> Result<Duration, Box<(dyn std::error::Error + Send + Sync)>> {
-        let ts = Instant::now();
-
-        let lines = input.lines();
-        let mut instrs = Vec::with_capacity(lines.clone().count());
+    let (mut x, mut y) = (0isize, 0isize);
 
-        for line in lines {
-            instrs.push(PlanItem::build(line, false));
-        }
+    map.verts.push((0, 0));
 
-        let map_range = map_limits(&instrs);
-        let mut map = Map::new(map_range, instrs.len());
+    instrs.iter_mut().for_each(|instr| {
+        instr.compute_coords(&mut x, &mut y);
+        map.add_circumference(instr.direction);
+        map.verts.push((x as usize, y as usize));
+        let _tmp = instr.get_norm_coords(map.limits);
+    });
 
-        let (mut x, mut y) = (0isize, 0isize);
+    map.area()
+}
 
-        map.verts.reserve(instrs.len());
-        map.verts.push((0, 0));
 
-        instrs.iter_mut().for_each(|instr| {
-            instr.compute_coords(&mut x, &mut y);
-            map.add_circumference(instr.direction);
-            map.verts.push((x as usize, y as usize));
-            let _tmp = instr.get_norm_coords(map.limits);
-        });
+#[solver(year = 2023, day = 18)]
+pub(crate) struct Day18;
 
-        let total = map.area();
 
-        let d = ts.elapsed();
+const GRID: &str = indoc! {"
+    R 6 (#70c710)
+    D 5 (#0dc571)
+    L 2 (#5713f0)
+    D 2 (#d2c081)
+    R 2 (#59c680)
+    D 2 (#411b91)
+    L 5 (#8ceee2)
+    U 2 (#caa173)
+    L 1 (#1b58a2)
+    U 2 (#caa171)
+    R 2 (#7807d2)
+    U 3 (#a77fa3)
+    L 2 (#015232)
+    U 2 (#7a21e3)
+"};
 
-        let mut output = out.lock().unwrap();
-        *output = total.into();
+impl Solver for Day18 {
+    type Input<'a> = (Vec<PlanItem>, Vec<PlanItem>);
 
-        Ok(d)
+    fn title(&self) -> &'static str {
+        r"Lavaduct Lagoon"
     }
 
-    fn part_b(&self, input: &str, out: std::sync::Arc<std::sync::Mutex<schema::Answer>>) 
-            -> Result<Duration, Box<(dyn std::error::Error + Send + Sync)>> {
-        let ts = Instant::now();
+    fn examples(&self) -> &'static [Example] {
+        const { &[Example::new("grid", GRID).part_a("62").part_b("952408144115")] }
+    }
 
-        let lines = input.lines();
+    /// Both readings of the dig plan: the plain one and the one hidden in
+    /// the colour codes.
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Input<'a>, BoxError> {
+        let lines = parse::lines(input);
         let mut instrs = Vec::with_capacity(lines.clone().count());
+        let mut radix = Vec::with_capacity(lines.clone().count());
 
         for line in lines {
-            instrs.push(PlanItem::build(line, true));
+            instrs.push(PlanItem::build(line, false)?);
+            radix.push(PlanItem::build(line, true)?);
         }
 
-        let map_range = map_limits(&instrs);
-        let mut map = Map::new(map_range, instrs.len());
-
-        let (mut x, mut y) = (0isize, 0isize);
-
-        map.verts.push((0, 0));
-
-        instrs.iter_mut().for_each(|instr| {
-            instr.compute_coords(&mut x, &mut y);
-            map.add_circumference(instr.direction);
-            map.verts.push((x as usize, y as usize));
-            let _tmp = instr.get_norm_coords(map.limits);
-        });
-
-        let total = map.area();
-
-        let d = ts.elapsed();
+        Ok((instrs, radix))
+    }
 
-        let mut output = out.lock().unwrap();
-        *output = total.into();
+    fn part_a(&self, (instrs, _): &Self::Input<'_>, _ctx: &Context) -> Result<Answer, BoxError> {
+        let total = dig(&mut instrs.clone());
 
-        Ok(d)
+        Ok(total.into())
     }
 
-    fn is_empty(&self) -> bool {
-        false
+    fn part_b(&self, (_, instrs): &Self::Input<'_>, _ctx: &Context) -> Result<Answer, BoxError> {
+        let total = dig(&mut instrs.clone());
+
+        Ok(total.into())
     }
 }
 
 #[cfg(test)]
 mod test {
-    use std::{sync::Arc, sync::Mutex};
-
     use schema::*;
 
-    use indoc::indoc;
-
-    use super::Day18;
-
-    const GRID: &str = indoc! {"
-        R 6 (#70c710)
-        D 5 (#0dc571)
-        L 2 (#5713f0)
-        D 2 (#d2c081)
-        R 2 (#59c680)
-        D 2 (#411b91)
-        L 5 (#8ceee2)
-        U 2 (#caa173)
-        L 1 (#1b58a2)
-        U 2 (#caa171)
-        R 2 (#7807d2)
-        U 3 (#a77fa3)
-        L 2 (#015232)
-        U 2 (#7a21e3)
-    "};
+    use super::{Day18, GRID};
 
     #[test]
     fn part_a() {
-        let out = Arc::new(Mutex::new(Answer::Unimplemented));
-        let _ = Day18.part_a(GRID, out.clone());
-        assert_eq!(*out.lock().unwrap(), Answer::Number(62));
+        let input = Day18.parse(GRID).unwrap();
+        assert_eq!(Day18.part_a(&input, &Context::default()).unwrap(), Answer::Number(62));
     }
 
     #[test]
     fn part_b() {
-        let out = Arc::new(Mutex::new(Answer::Unimplemented));
-        let _ = Day18.part_b(GRID, out.clone());
-        assert_eq!(*out.lock().unwrap(), Answer::Number(952408144115));
+        let input = Day18.parse(GRID).unwrap();
+        assert_eq!(Day18.part_b(&input, &Context::default()).unwrap(), Answer::Number(952408144115));
     }
 
+    #[test]
+    fn bad_direction() {
+        let err = Day18.parse("R 6 (#70c710)\nX 5 (#0dc571)").err().unwrap();
+        let err = err.downcast::<SolveError>().unwrap();
+
+        assert_eq!((err.line, err.column), (2, 1));
+        assert_eq!(err.found, "X");
+    }
 }
@@ -1,9 +1,8 @@
-use std::time::{Duration, Instant};
-
 use ahash::{HashMap, HashMapExt};
 
 
-use schema::Solver;
+use indoc::indoc;
+use schema::{parse, Answer, BoxError, Context, Example, solver, Solver};
 
 
 #[derive(Debug, PartialEq, Eq)]
@@ -13,45 +12,51 @@ enum Compare {
     None
 }
 
-fn parse(input: &str) -> AsmIns<'_> {
+fn parse(input: &str) -> Result<AsmIns<'_>, BoxError> {
 
-    let (rs, ps) = input.split_once("\n\n").unwrap();
+    let mut lines = parse::lines(input);
 
-    let mut rules = HashMap::with_capacity(rs.len());
-    let mut parts = Vec::with_capacity(ps.len());
+    let mut rules = HashMap::new();
+    let mut parts = Vec::new();
+    let mut dests = Vec::new();
 
-    for r in rs.lines() {
+    for line in lines.by_ref().take_while(|l| !l.text.is_empty()) {
 
         let mut ruleset = Ruleset::default();
 
-        let (n, rule_s) = r.split_once('{').unwrap();
+        let (n, rule_s) = line.split_once("{")?;
+        let rule_s = rule_s.strip_suffix('}').ok_or_else(|| line.error_eol("`}`"))?;
 
         let mut rts = Vec::new();
-        for rt in rule_s.trim_end_matches('}').split(',') {
+        for rt in rule_s.split(',') {
             let Some((ops, res)) = rt.split_once(':') else {
+                dests.push((line, rt));
                 rts.push(Rule::Default(rt));
                 continue;
             };
 
-            let pt: Vec<_> = ops.split_inclusive(|c| c == '<' || c == '>' ).collect();
-            let (p, op) = pt[0].split_at(1);
-            let val = pt[1];
+            let p = match ops.as_bytes().first() {
+                Some(&p @ (b'x' | b'm' | b'a' | b's')) => p,
+                _ => return Err(line.error_on(ops, "one of `x`, `m`, `a` or `s`").into()),
+            };
 
-            let out = match res.len() {
-                1 if res == "A" || res == "R" => Outcome::from(*res.as_bytes().first().unwrap()),
-                2 | 3 => Outcome::from(res),
-                _ => panic!("Invalid Outcome"),
+            let out = match res {
+                "A" | "R" => Outcome::from(res.as_bytes()[0]),
+                _ => {
+                    dests.push((line, res));
+                    Outcome::from(res)
+                }
             };
 
             let mut top = Ops::new();
 
-            top.p = p.as_bytes()[0];
-            top.op = match op.as_bytes()[0] {
-                b'>' => Compare::GT,
-                b'<' => Compare::LT,
-                _ => unreachable!()
+            top.p = p;
+            top.op = match ops.as_bytes().get(1) {
+                Some(b'>') => Compare::GT,
+                Some(b'<') => Compare::LT,
+                _ => return Err(line.error_on(&ops[1..], "`<` or `>`").into()),
             };
-            top.value = val.parse::<u64>().expect("Invalid value");
+            top.value = line.parse(&ops[2..], "a rating")?;
 
             top.init_path(out);
 
@@ -64,19 +69,33 @@ fn parse(input: &str) -> AsmIns<'_> {
 
     }
 
-    for p in ps.lines() {
-        let inner = p.trim_matches(|c| c == '{' || c == '}').split(',');
+    // Check every workflow a rule sends parts to, so solving can't get lost.
+    for (line, dest) in dests {
+        if dest != "A" && dest != "R" && !rules.contains_key(dest) {
+            return Err(line.error_on(dest, "the name of a workflow").into());
+        }
+    }
+
+    if !rules.contains_key("in") {
+        return Err("there's no `in` workflow to start from".into());
+    }
+
+    for line in lines {
+        let inner = line.text.strip_prefix('{')
+                             .and_then(|p| p.strip_suffix('}'))
+                             .ok_or_else(|| line.error_at(0, "a part like `{x=1,m=2,a=3,s=4}`"))?;
 
         let mut num = Part::default();
-        for t in inner {
-            let (n, v) = t.split_once('=').unwrap();
+        for t in inner.split(',') {
+            let (n, v) = t.split_once('=').ok_or_else(|| line.error_on(t, "`<category>=<rating>`"))?;
+            let v = line.parse(v, "a rating")?;
 
             match n {
-                "x" => num.x = v.parse::<u64>().unwrap(),
-                "m" => num.m = v.parse::<u64>().unwrap(),
-                "a" => num.a = v.parse::<u64>().unwrap(),
-                "s" => num.s = v.parse::<u64>().unwrap(),
-                _   => unreachable!()
+                "x" => num.x = v,
+                "m" => num.m = v,
+                "a" => num.a = v,
+                "s" => num.s = v,
+                _   => return Err(line.error_on(n, "one of `x`, `m`, `a` or `s`").into()),
             }
         }
 
@@ -84,7 +103,7 @@ fn parse(input: &str) -> AsmIns<'_> {
 
     }
 
-    AsmIns { rules, parts }
+    Ok(AsmIns { rules, parts })
 
 }
 
@@ -115,23 +134,18 @@ impl Part {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Default)]
 #[repr(transparent)]
 struct Ruleset<'a> (Vec<Rule<'a>>);
 
-impl Default for Ruleset<'_> {
-    fn default() -> Self {
-        Self(Vec::new())
-    }
-}
-
 #[derive(Debug, Default)]
-struct AsmIns<'a> {
+pub(crate) struct AsmIns<'a> {
     rules: HashMap<&'a str, Ruleset<'a>>,
     parts: Vec<Part>
 }
 
 #[derive(Debug, Default)]
+#[allow(clippy::enum_variant_names)]
 enum Rule<'a> {
     Rule(Ops<'a>),
     Default(&'a str),
@@ -171,27 +185,22 @@ impl<'a> Ops<'a> {
 
 }
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
 #[repr(u8)]
 enum Outcome<'a> {
     Reject = b'R',
     Accept = b'A',
     R(&'a str),
+    #[default]
     None = b'\0',
 }
 
-impl Default for Outcome<'_> {
-    fn default() -> Self {
-        Self::None
-    }
-}
-
 impl Outcome<'_> {
     fn as_str(&self) -> &'_ str {
         match self {
             Self::Accept => "A",
             Self::Reject => "R",
-            Self::R(a)   => *a,
+            Self::R(a)   => a,
             _ => unreachable!()
         }
     }
@@ -215,7 +224,7 @@ impl<'a> From<&'a str> for Outcome<'a> {
 
 #[inline(always)]
 fn size(ranges: [(u64, u64); 4]) -> u64 {
-    ranges.iter().map(|&(f, s)| s - f + 1).fold(1, |acc, i| acc * i)
+    ranges.iter().map(|&(f, s)| s - f + 1).product()
 }
 
 fn dyn_part_b(rules: &HashMap<&str, Ruleset<'_>>, mut range: [(u64, u64); 4], inp: &str) -> u64 {
@@ -230,7 +239,8 @@ fn dyn_part_b(rules: &HashMap<&str, Ruleset<'_>>, mut range: [(u64, u64); 4], in
         }
     };
 
-    for rule in &rules.get(inp).unwrap().0 {
+    // Parsing checked every destination names a workflow.
+    for rule in &rules[inp].0 {
         match rule {
             Rule::Rule(a) => {
                                 let mut n_r = range;
@@ -263,26 +273,56 @@ fn dyn_part_b(rules: &HashMap<&str, Ruleset<'_>>, mut range: [(u64, u64); 4], in
     ans
 }
 
-pub struct Day19;
+#[solver(year = 2023, day = 19)]
+pub(crate) struct Day19;
+
+const CASE: &str = indoc! {"
+    px{a<2006:qkq,m>2090:A,rfg}
+    pv{a>1716:R,A}
+    lnx{m>1548:A,A}
+    rfg{s<537:gd,x>2440:R,A}
+    qs{s>3448:A,lnx}
+    qkq{x<1416:A,crn}
+    crn{x>2662:A,R}
+    in{s<1351:px,qqz}
+    qqz{s>2770:qs,m<1801:hdj,R}
+    gd{a>3333:R,R}
+    hdj{m>838:A,pv}
+
+    {x=787,m=2655,a=1222,s=2876}
+    {x=1679,m=44,a=2067,s=496}
+    {x=2036,m=264,a=79,s=2244}
+    {x=2461,m=1339,a=466,s=291}
+    {x=2127,m=1623,a=2188,s=1013}
+"};
 
 impl Solver for Day19 {
+    type Input<'a> = AsmIns<'a>;
+
     fn title(&self) -> &'static str {
         r"Aplenty"
     }
 
-    fn part_a(&self, input: &str, out: std::sync::Arc<std::sync::Mutex<schema::Answer>>) 
-            -> Result<Duration, Box<(dyn std::error::Error + Send + Sync)>> {
+    fn examples(&self) -> &'static [Example] {
+        const { &[Example::new("case", CASE).part_a("19114").part_b("167409079868000")] }
+    }
 
-        let mut ans = 0;
-        let ts = Instant::now();
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Input<'a>, BoxError> {
+        parse(input)
+    }
+
+    fn part_a(&self, part_list: &Self::Input<'_>, ctx: &Context) -> Result<Answer, BoxError> {
 
-        let part_list = parse(input);
+        let mut ans = 0;
 
-        for part in part_list.parts {
+        for part in part_list.parts.iter() {
             let mut pt: &str = r"in";
 
+            // Workflows that send parts round in a circle would spin forever.
             loop {
-                let work = part_list.rules.get(pt).expect("BAD PARSE");
+                ctx.check()?;
+
+                let work = &part_list.rules[pt];
 
                 for rule in &work.0 {
                     match rule {
@@ -317,79 +357,48 @@ impl Solver for Day19 {
             }
         }
 
-        let d = ts.elapsed();
-
-        let mut output = out.lock().unwrap();
-        *output = ans.into();
-
-        Ok(d)
+        Ok(ans.into())
     }
 
-    fn part_b(&self, input: &str, out: std::sync::Arc<std::sync::Mutex<schema::Answer>>) 
-            -> Result<Duration, Box<(dyn std::error::Error + Send + Sync)>> {
+    fn part_b(&self, part_list: &Self::Input<'_>, _ctx: &Context) -> Result<Answer, BoxError> {
 
         let range = [(1, 4000); 4];
 
-        let ts = Instant::now();
-
-        let part_list = parse(input);
-
         let ans = dyn_part_b(&part_list.rules, range, r"in");
 
-        let d = ts.elapsed();
-
-        let mut output = out.lock().unwrap();
-        *output = ans.into();
-
-        Ok(d)
-    }
-
-    fn is_empty(&self) -> bool {
-        false
+        Ok(ans.into())
     }
 }
 
 #[cfg(test)]
 mod test {
-    use std::{sync::Arc, sync::Mutex};
-
     use schema::*;
 
-    use indoc::indoc;
-
-    use super::Day19;
-
-    const CASE: &str = indoc! {"
-        px{a<2006:qkq,m>2090:A,rfg}
-        pv{a>1716:R,A}
-        lnx{m>1548:A,A}
-        rfg{s<537:gd,x>2440:R,A}
-        qs{s>3448:A,lnx}
-        qkq{x<1416:A,crn}
-        crn{x>2662:A,R}
-        in{s<1351:px,qqz}
-        qqz{s>2770:qs,m<1801:hdj,R}
-        gd{a>3333:R,R}
-        hdj{m>838:A,pv}
-
-        {x=787,m=2655,a=1222,s=2876}
-        {x=1679,m=44,a=2067,s=496}
-        {x=2036,m=264,a=79,s=2244}
-        {x=2461,m=1339,a=466,s=291}
-        {x=2127,m=1623,a=2188,s=1013}
-    "};
+    use super::{Day19, CASE};
 
     #[test]
     fn part_a() {
-        let out = Arc::new(Mutex::new(Answer::Unimplemented));
-        let _ = Day19.part_a(CASE, out.clone());
-        assert_eq!(*out.lock().unwrap(), Answer::Number(19114));
+        let input = Day19.parse(CASE).unwrap();
+        assert_eq!(Day19.part_a(&input, &Context::default()).unwrap(), Answer::Number(19114));
     }
 
     #[test]
     fn part_b() {
-        let out = Arc::new(Mutex::new(Answer::Unimplemented));
-        let _ = Day19.part_b(CASE, out.clone());
-        assert_eq!(*out.lock().unwrap(), Answer::Number(167409079868000_u64));
+        let input = Day19.parse(CASE).unwrap();
+        assert_eq!(Day19.part_b(&input, &Context::default()).unwrap(), Answer::Number(167409079868000_u64));
+    }
+
+    #[test]
+    fn unknown_workflow() {
+        let err = Day19.parse("in{x<10:px,A}\n\n{x=1,m=2,a=3,s=4}").err().unwrap();
+        let err = err.downcast::<SolveError>().unwrap();
+        assert_eq!((err.line, err.column, err.found.as_str()), (1, 9, "px"));
+    }
+
+    #[test]
+    fn bad_rating() {
+        let err = Day19.parse("in{A}\n\n{x=1,m=two,a=3,s=4}").err().unwrap();
+        let err = err.downcast::<SolveError>().unwrap();
+        assert_eq!((err.line, err.column), (3, 8));
     }
 }
@@ -1,14 +1,18 @@
-use std::{time::{Duration, Instant}, error::Error};
+use schema::{parse::{self, Line}, Answer, BoxError, Context, SolveError, solver, Solver};
 
-use schema::Solver;
 
+#[solver(year = 2023, day = 2)]
+pub(crate) struct Day02;
 
-pub struct Day02;
+/// A game's id followed by the most red, green and blue cubes seen.
+type Game = (u32, u32, u32, u32);
 
-fn line_parse(line: &str) -> Result<(u32, u32, u32, u32), Box<dyn Error + Send + Sync>> {
-    let (game, lgame) = line.split_once(':').unwrap();
+fn line_parse(line: Line<'_>) -> Result<Game, SolveError> {
+    let (game, lgame) = line.split_once(":")?;
 
-    let id = game.split_at(5).1.parse::<u32>().unwrap();
+    let id = game.strip_prefix("Game ")
+                 .ok_or_else(|| line.error_on(game, "`Game <id>`"))?;
+    let id = line.parse::<u32>(id, "a game id")?;
 
     let mut red = 0;
     let mut blue = 0;
@@ -17,16 +21,14 @@ fn line_parse(line: &str) -> Result<(u32, u32, u32, u32), Box<dyn Error + Send +
     for set in lgame.split_terminator(&[';',',']) {
         let mut c = set.split_whitespace();
 
-        let val = c.next().unwrap().parse::<u32>().unwrap();
+        let val = line.next(&mut c, "a cube count")?;
+        let val = line.parse::<u32>(val, "a cube count")?;
 
-        match c.next().unwrap_or_default() {
+        match line.next(&mut c, "a colour")? {
             "red"   => { red = red.max(val);        },
             "green" => { green = green.max(val);    },
             "blue"  => { blue = blue.max(val);      },
-            _ => {
-                eprintln!("Found invalid color at {id}");
-                return Err(Box::<dyn Error + Send + Sync>::from("Bad input!"));
-            },
+            color   => return Err(line.error_on(color, "`red`, `green` or `blue`")),
         }
 
     }
@@ -36,51 +38,37 @@ fn line_parse(line: &str) -> Result<(u32, u32, u32, u32), Box<dyn Error + Send +
 }
 
 impl Solver for Day02 {
+    type Input<'a> = Vec<Game>;
+
     fn title(&self) -> &'static str {
         r"Cube Conundrum"
     }
 
-    fn part_a(&self, input: &str, out: std::sync::Arc<std::sync::Mutex<schema::Answer>>) 
-            -> Result<Duration, Box<(dyn std::error::Error + Send + Sync)>> {
-        let mut total = 0;
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Input<'a>, BoxError> {
+        Ok(parse::lines(input).map(line_parse).collect::<Result<_, _>>()?)
+    }
 
-        let ts = Instant::now();
-        for line in input.lines() {
-            let (id, red, green, blue) = line_parse(line)?;
+    fn part_a(&self, input: &Self::Input<'_>, _ctx: &Context) -> Result<Answer, BoxError> {
+        let mut total = 0;
 
+        for &(id, red, green, blue) in input.iter() {
             if (red <= 12) && (green <= 13) && (blue <= 14) {
                 total += id;
             }
 
         }
-        let d = ts.elapsed();
-
-        let mut output = out.lock().unwrap();
-        *output = total.into();
 
-        Ok(d)
+        Ok(total.into())
     }
 
-    fn part_b(&self, input: &str, out: std::sync::Arc<std::sync::Mutex<schema::Answer>>) 
-            -> Result<Duration, Box<(dyn std::error::Error + Send + Sync)>> {
+    fn part_b(&self, input: &Self::Input<'_>, _ctx: &Context) -> Result<Answer, BoxError> {
         let mut total = 0;
 
-        let ts = Instant::now();
-        for line in input.lines() {
-            let (_, red, green, blue) = line_parse(line)?;
-
+        for &(_, red, green, blue) in input.iter() {
             total += red * green * blue;
 
         }
-        let d = ts.elapsed();
-
-        let mut output = out.lock().unwrap();
-        *output = total.into();
-
-        Ok(d)
-    }
 
-    fn is_empty(&self) -> bool {
-        false
+        Ok(total.into())
     }
 }
\ No newline at end of file
@@ -1,7 +1,7 @@
-use std::time::{Duration, Instant};
 use ahash::AHashMap;
 
-use schema::Solver;
+use indoc::indoc;
+use schema::{Answer, BoxError, Context, Example, solver, Solver};
 use crate::problem::day3::Found::*;
 
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -44,11 +44,18 @@ impl Default for Found {
     }
 }
 
+/// Every part symbol and part number found on the engine schematic.
+#[derive(Debug, Default)]
+pub(crate) struct Schematic {
+    parts: AHashMap<(u32, u32), char>,
+    numbers: AHashMap<Position, u32>,
+    max_x: usize,
+    max_y: usize,
+}
+
 /// An interesting algorithm to find all the part numbers and the parts in the
-/// input, then for each part look around and find the part numbers.
-/// 
-/// In the case of gears, the filter is easier!
-fn find_parts(page: &str, is_ratio: bool) -> Found {
+/// input, so that each part can look around and find the part numbers.
+fn scan(page: &str) -> Schematic {
 
     let mut parts: AHashMap<(u32, u32), char> = AHashMap::new();
     let mut numbers: AHashMap<Position, u32> = AHashMap::new();
@@ -102,6 +109,17 @@ fn find_parts(page: &str, is_ratio: bool) -> Found {
         }
     }
 
+    Schematic { parts, numbers, max_x, max_y }
+}
+
+/// For each part look around and find the part numbers.
+/// 
+/// In the case of gears, the filter is easier!
+fn find_parts(schematic: &Schematic, is_ratio: bool) -> Found {
+
+    let Schematic { parts, numbers, max_x, max_y } = schematic;
+    let (max_x, max_y) = (*max_x, *max_y);
+
     if !is_ratio {
         // This resembles bounding box aabb detection
         let found_value = |r: Position, i| {
@@ -154,106 +172,96 @@ fn find_parts(page: &str, is_ratio: bool) -> Found {
     }
 }
 
-pub struct Day03;
+#[solver(year = 2023, day = 3)]
+pub(crate) struct Day03;
+
+const PUZZLE: &str = indoc! {"
+    467..114..
+    ...*......
+    ..35..633.
+    ......#...
+    617*......
+    .....+.58.
+    ..592.....
+    ......755.
+    ...$.*....
+    .664.598..
+"};
+
+const PUZZLE_ONE: &str = indoc! {"
+    467..114..
+    ...*......
+    ..35..633.
+    ......#...
+    617*11.111
+    2....+.58.
+    ..592.....
+    ......755.
+    ...$.*....
+    .664.598..
+"};
 
 impl Solver for Day03 {
+    type Input<'a> = Schematic;
 
     fn title(&self) -> &'static str {
         r"Gear Ratios"
     }
 
-    fn part_a(&self, input: &str, out: std::sync::Arc<std::sync::Mutex<schema::Answer>>) 
-            -> Result<Duration, Box<(dyn std::error::Error + Send + Sync)>> {
-        let ts = Instant::now();
-        let ans = find_parts(input, false).get_part();
-        let d = ts.elapsed();
-
-        let mut output = out.lock().unwrap();
-        *output = ans.into();
-
-        Ok(d)
+    fn examples(&self) -> &'static [Example] {
+        const {
+            &[
+                Example::new("puzzle", PUZZLE).part_a("4361").part_b("467835"),
+                Example::new("puzzle_one", PUZZLE_ONE).part_a("4483").part_b("474622"),
+            ]
+        }
     }
 
-    fn part_b(&self, input: &str, out: std::sync::Arc<std::sync::Mutex<schema::Answer>>) 
-            -> Result<Duration, Box<(dyn std::error::Error + Send + Sync)>> {
-        let ts = Instant::now();
-        let ans = find_parts(input, true).get_ratio();
-        let d = ts.elapsed();
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Input<'a>, BoxError> {
+        Ok(scan(input))
+    }
 
-        let mut output = out.lock().unwrap();
-        *output = ans.into();
+    fn part_a(&self, input: &Self::Input<'_>, _ctx: &Context) -> Result<Answer, BoxError> {
+        let ans = find_parts(input, false).get_part();
 
-        Ok(d)
+        Ok(ans.into())
     }
 
-    fn is_empty(&self) -> bool {
-        false
+    fn part_b(&self, input: &Self::Input<'_>, _ctx: &Context) -> Result<Answer, BoxError> {
+        let ans = find_parts(input, true).get_ratio();
+
+        Ok(ans.into())
     }
 
 }
 
 #[cfg(test)]
 mod test {
-
-    use std::{sync::Arc, sync::Mutex, ops::Deref};
-
     use schema::*;
 
-    use indoc::indoc;
-
-    use super::Day03;
-
-    const PUZZLE: &str = indoc! {"
-        467..114..
-        ...*......
-        ..35..633.
-        ......#...
-        617*......
-        .....+.58.
-        ..592.....
-        ......755.
-        ...$.*....
-        .664.598..
-    "};
-
-    const PUZZLE_ONE: &str = indoc! {"
-        467..114..
-        ...*......
-        ..35..633.
-        ......#...
-        617*11.111
-        2....+.58.
-        ..592.....
-        ......755.
-        ...$.*....
-        .664.598..
-    "};
+    use super::{Day03, PUZZLE, PUZZLE_ONE};
 
     #[test]
     fn part_a() {
-        let out = Arc::new(Mutex::new(Answer::Unimplemented));
-        let _ = Day03.part_a(PUZZLE, out.clone());
-        assert_eq!(*out.lock().unwrap().deref(), Answer::Number(4361));
+        let input = Day03.parse(PUZZLE).unwrap();
+        assert_eq!(Day03.part_a(&input, &Context::default()).unwrap(), Answer::Number(4361));
     }
 
     #[test]
     fn part_a_mod() {
-        let out = Arc::new(Mutex::new(Answer::Unimplemented));
-        let _ = Day03.part_a(PUZZLE_ONE, out.clone());
-        assert_eq!(*out.lock().unwrap().deref(), Answer::Number(4483));
+        let input = Day03.parse(PUZZLE_ONE).unwrap();
+        assert_eq!(Day03.part_a(&input, &Context::default()).unwrap(), Answer::Number(4483));
     }
 
     #[test]
     fn part_b() {
-        let out = Arc::new(Mutex::new(Answer::Unimplemented));
-        let _ = Day03.part_b(PUZZLE, out.clone());
-        assert_eq!(*out.lock().unwrap().deref(), Answer::Number(467835));
+        let input = Day03.parse(PUZZLE).unwrap();
+        assert_eq!(Day03.part_b(&input, &Context::default()).unwrap(), Answer::Number(467835));
     }
 
     #[test]
     fn part_b_mod() {
-        let out = Arc::new(Mutex::new(Answer::Unimplemented));
-        let _ = Day03.part_b(PUZZLE_ONE, out.clone());
-        assert_eq!(*out.lock().unwrap().deref(), Answer::Number(474622));
+        let input = Day03.parse(PUZZLE_ONE).unwrap();
+        assert_eq!(Day03.part_b(&input, &Context::default()).unwrap(), Answer::Number(474622));
     }
 }
\ No newline at end of file
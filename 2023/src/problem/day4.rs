@@ -1,10 +1,10 @@
-use std::time::{Duration, Instant};
 use ahash::AHashSet;
 
-use schema::Solver;
+use indoc::indoc;
+use schema::{Answer, BoxError, Context, Example, solver, Solver};
 
 #[derive(Debug, Default)]
-struct Card {
+pub(crate) struct Card {
     winning_nums: AHashSet<u32>,
     scratch_nums: AHashSet<u32>,
 
@@ -24,18 +24,32 @@ impl Card {
     }
 }
 
-pub struct Day04;
+#[solver(year = 2023, day = 4)]
+pub(crate) struct Day04;
+
+const CARDS: &str = indoc! {"
+    Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+    Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
+    Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
+    Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
+    Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
+    Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11
+"};
 
 impl Solver for Day04 {
+    type Input<'a> = Vec<Card>;
+
     fn title(&self) -> &'static str {
         r"Scratchcards"
     }
 
-    fn part_a(&self, input: &str, out: std::sync::Arc<std::sync::Mutex<schema::Answer>>) 
-            -> Result<Duration, Box<(dyn std::error::Error + Send + Sync)>> {
+    fn examples(&self) -> &'static [Example] {
+        const { &[Example::new("cards", CARDS).part_a("13").part_b("30")] }
+    }
+
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Input<'a>, BoxError> {
         let mut cards: Vec<Card> = Vec::new();
 
-        let ts = Instant::now();
         for line in input.lines() {
             let (_, game) = line.split_once(':').unwrap();
 
@@ -55,51 +69,31 @@ impl Solver for Day04 {
             cards.push(dummy);
         }
 
+        Ok(cards)
+    }
+
+    fn part_a(&self, cards: &Self::Input<'_>, _ctx: &Context) -> Result<Answer, BoxError> {
         let mut total = 0;
 
         for c in cards.iter().filter(|x| !x.win_nums.is_empty()) {
             total += c.total();
         }
 
-        let d = ts.elapsed();
-
-        let mut output = out.lock().unwrap();
-        *output = total.into();
-
-        Ok(d)
+        Ok(total.into())
 
     }
 
-    fn part_b(&self, input: &str, out: std::sync::Arc<std::sync::Mutex<schema::Answer>>) 
-            -> Result<Duration, Box<(dyn std::error::Error + Send + Sync)>> {
-        let mut cards: Vec<Card> = Vec::new();
-
-        let ts = Instant::now();
-        for line in input.lines() {
-            let (_, game) = line.split_once(':').unwrap();
-
-            let (win, scratch) = game.trim().split_once('|').unwrap();
-
-            let mut dummy = Card::default();
-
-            for w in win.split_whitespace() {
-                dummy.winning_nums.insert(w.parse::<u32>().unwrap());
-            }
-
-            for s in scratch.split_whitespace() {
-                dummy.scratch_nums.insert(s.parse::<u32>().unwrap());
-            }
-
-            dummy.find_winnings();
-            cards.push(dummy);
-        }
-
+    fn part_b(&self, cards: &Self::Input<'_>, ctx: &Context) -> Result<Answer, BoxError> {
         let mut total = 0;
         let mut q = (0..cards.len()).collect::<Vec<usize>>();
 
         while let Some(i) = q.pop() {
             total += 1;
 
+            if total % 4096 == 0 {
+                ctx.check()?;
+            }
+
             let c = &cards[i];
             if !c.win_nums.is_empty() {
                 for w in 0..c.win_nums.len() {
@@ -108,50 +102,27 @@ impl Solver for Day04 {
             }
 
         }
-        let d = ts.elapsed();
 
-        let mut output = out.lock().unwrap();
-        *output = total.into();
-
-        Ok(d)
+        Ok(total.into())
 
     }
-
-    fn is_empty(&self) -> bool {
-        false
-    }
 }
 
 #[cfg(test)]
 mod test {
-    use std::{sync::Arc, sync::Mutex, ops::Deref};
-
     use schema::*;
 
-    use indoc::indoc;
-
-    use super::Day04;
-
-    const CARDS: &str = indoc! {"
-        Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
-        Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
-        Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
-        Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
-        Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
-        Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11
-    "};
+    use super::{Day04, CARDS};
 
     #[test]
     fn part_a() {
-        let out = Arc::new(Mutex::new(Answer::Unimplemented));
-        let _ = Day04.part_a(CARDS, out.clone());
-        assert_eq!(*out.lock().unwrap().deref(), Answer::Number(13));
+        let input = Day04.parse(CARDS).unwrap();
+        assert_eq!(Day04.part_a(&input, &Context::default()).unwrap(), Answer::Number(13));
     }
 
     #[test]
     fn part_b() {
-        let out = Arc::new(Mutex::new(Answer::Unimplemented));
-        let _ = Day04.part_b(CARDS, out.clone());
-        assert_eq!(*out.lock().unwrap().deref(), Answer::Number(30));
+        let input = Day04.parse(CARDS).unwrap();
+        assert_eq!(Day04.part_b(&input, &Context::default()).unwrap(), Answer::Number(30));
     }
 }
@@ -1,16 +1,16 @@
 use std::{
-    time::{Duration, Instant}, 
     collections::{BTreeMap, VecDeque}, 
 };
 
-use schema::Solver;
+use indoc::indoc;
+use schema::{Answer, BoxError, Context, Example, solver, Solver};
 
 /// The core entity for all the mappings
 /// Data is stored as key = source, value = (destination, range)
 type Map = BTreeMap<u64, (u64, u64)>;
 
 #[derive(Debug, Default)]
-struct Almanac {
+pub(crate) struct Almanac {
     seeds: Vec<u64>,
 
     maps: Vec<Map>
@@ -80,46 +80,81 @@ impl Almanac {
 
 
 
-pub struct Day05;
+#[solver(year = 2023, day = 5)]
+pub(crate) struct Day05;
+
+const PROBLEM: &str = indoc! {"
+    seeds: 79 14 55 13
+
+    seed-to-soil map:
+    50 98 2
+    52 50 48
+
+    soil-to-fertilizer map:
+    0 15 37
+    37 52 2
+    39 0 15
+
+    fertilizer-to-water map:
+    49 53 8
+    0 11 42
+    42 0 7
+    57 7 4
+
+    water-to-light map:
+    88 18 7
+    18 25 70
+
+    light-to-temperature map:
+    45 77 23
+    81 45 19
+    68 64 13
+
+    temperature-to-humidity map:
+    0 69 1
+    1 0 69
+
+    humidity-to-location map:
+    60 56 37
+    56 93 4
+"};
 
 impl Solver for Day05 {
+    type Input<'a> = Almanac;
+
     fn title(&self) -> &'static str {
         r"If You Give A Seed A Fertilizer"
     }
 
-    fn part_a(&self, input: &str, out: std::sync::Arc<std::sync::Mutex<schema::Answer>>) 
-            -> Result<Duration, Box<(dyn std::error::Error + Send + Sync)>> {
-        
-        let ts = Instant::now();
-        let almanac = Almanac::build_almanac(input);
+    fn examples(&self) -> &'static [Example] {
+        const { &[Example::new("problem", PROBLEM).part_a("35").part_b("46")] }
+    }
+
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Input<'a>, BoxError> {
+        Ok(Almanac::build_almanac(input))
+    }
 
+    fn part_a(&self, almanac: &Self::Input<'_>, _ctx: &Context) -> Result<Answer, BoxError> {
+        
         let mut location = u64::MAX;
 
         for seed in almanac.seeds.iter() {
             location = location.min(almanac.walk(*seed));
         }
 
-        let d = ts.elapsed();
-
-        let mut output = out.lock().unwrap();
-        *output = location.into();
-
-        Ok(d)
+        Ok(location.into())
         
     }
 
-    fn part_b(&self, input: &str, out: std::sync::Arc<std::sync::Mutex<schema::Answer>>) 
-            -> Result<Duration, Box<(dyn std::error::Error + Send + Sync)>> {
+    fn part_b(&self, almanac: &Self::Input<'_>, ctx: &Context) -> Result<Answer, BoxError> {
         
-        let ts = Instant::now();
-        let almanac = Almanac::build_almanac(input);
-
         // Co-opted this solution:
         // https://github.com/Fabi019/aoc2023/blob/main/src/bin/day05.rs#L38
         let mut seeds: Vec<(u64, u64)> = VecDeque::new().into();
         seeds.extend(almanac.seeds.chunks_exact(2).map(|s| (s[0], s[0] + s[1])));
 
-        for map in almanac.maps.iter() {
+        for (index, map) in almanac.maps.iter().enumerate() {
+            let _span = tracing::debug_span!("map", index, ranges = seeds.len()).entered();
 
             let map_ranges: Vec<(u64, u64, u64)> = map.iter().map(|(s, (d, r))| (*d, *s, *s+*r)).collect();
 
@@ -129,6 +164,8 @@ impl Solver for Day05 {
             let mut new_q: Vec<(u64, u64)> = Vec::new();
 
             while let Some((start, end)) = queue.pop_front() {
+                ctx.check()?;
+
                 let mut found = false;
 
                 for &(target, lower, upper) in &map_ranges {
@@ -177,76 +214,25 @@ impl Solver for Day05 {
 
         let location = seeds.iter().min().unwrap().0;
 
-        let d = ts.elapsed();
-
-        let mut output = out.lock().unwrap();
-        *output = location.into();
-
-        Ok(d)
-    }
-
-    fn is_empty(&self) -> bool {
-        false
+        Ok(location.into())
     }
 }
 
 #[cfg(test)]
 mod test {
-    use std::{sync::Arc, sync::Mutex, ops::Deref};
-
     use schema::*;
 
-    use indoc::indoc;
-
-    use super::Day05;
-
-    const PROBLEM: &str = indoc! {"
-        seeds: 79 14 55 13
-
-        seed-to-soil map:
-        50 98 2
-        52 50 48
-
-        soil-to-fertilizer map:
-        0 15 37
-        37 52 2
-        39 0 15
-
-        fertilizer-to-water map:
-        49 53 8
-        0 11 42
-        42 0 7
-        57 7 4
-
-        water-to-light map:
-        88 18 7
-        18 25 70
-
-        light-to-temperature map:
-        45 77 23
-        81 45 19
-        68 64 13
-
-        temperature-to-humidity map:
-        0 69 1
-        1 0 69
-
-        humidity-to-location map:
-        60 56 37
-        56 93 4
-    "};
+    use super::{Day05, PROBLEM};
 
     #[test]
     fn part_a() {
-        let out = Arc::new(Mutex::new(Answer::Unimplemented));
-        let _ = Day05.part_a(PROBLEM, out.clone());
-        assert_eq!(*out.lock().unwrap().deref(), Answer::Number(35));
+        let input = Day05.parse(PROBLEM).unwrap();
+        assert_eq!(Day05.part_a(&input, &Context::default()).unwrap(), Answer::Number(35));
     }
 
     #[test]
     fn part_b() {
-        let out = Arc::new(Mutex::new(Answer::Unimplemented));
-        let _ = Day05.part_b(PROBLEM, out.clone());
-        assert_eq!(*out.lock().unwrap().deref(), Answer::Number(46));
+        let input = Day05.parse(PROBLEM).unwrap();
+        assert_eq!(Day05.part_b(&input, &Context::default()).unwrap(), Answer::Number(46));
     }
 }
@@ -1,84 +1,70 @@
-use std::time::{Duration, Instant};
+use schema::{parse, Answer, BoxError, Context, solver, Solver};
 
-use schema::Solver;
 
+/// The race sheet, one entry per column.
+#[derive(Debug, Default)]
+pub(crate) struct Races {
+    times: Vec<u64>,
+    dist: Vec<u64>,
+}
+
+/// Read the columns as one number, ignoring the bad kerning.
+fn kern(nums: &[u64]) -> u64 {
+    nums.iter().fold(0, |acc, &n| acc * 10u64.pow(n.checked_ilog10().unwrap_or(0) + 1) + n)
+}
 
-pub struct Day06;
+#[solver(year = 2023, day = 6)]
+pub(crate) struct Day06;
 
 
 impl Solver for Day06 {
+    type Input<'a> = Races;
     
     fn title(&self) -> &'static str {
         r"Wait For It"
     }
 
-    fn part_a(&self, input: &str, out: std::sync::Arc<std::sync::Mutex<schema::Answer>>) 
-            -> Result<Duration, Box<(dyn std::error::Error + Send + Sync)>> {
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Input<'a>, BoxError> {
+        let mut lines = parse::lines(input);
+
+        let mut row = |label: &str| -> Result<Vec<u64>, BoxError> {
+            let line = lines.next().ok_or_else(|| format!("expected a `{label}` line"))?;
+            let mut tokens = line.text.split_ascii_whitespace();
+
+            line.next(&mut tokens, label)?;
+
+            Ok(tokens.map(|s| line.parse(s, "a number")).collect::<Result<_, _>>()?)
+        };
+
+        let times = row("Time:")?;
+        let dist = row("Distance:")?;
+
+        Ok(Races { times, dist })
+    }
+
+    fn part_a(&self, races: &Self::Input<'_>, _ctx: &Context) -> Result<Answer, BoxError> {
         
-        let ts = Instant::now();
-        let mut lines = input.lines();
-
-        let times: Vec<u32> = lines.next().unwrap()
-                                        .split_ascii_whitespace()
-                                        .skip(1)
-                                        .map(|s| s.parse().unwrap())
-                                        .collect();
-
-        let dist: Vec<u32> = lines.next().unwrap()
-                                        .split_ascii_whitespace()
-                                        .skip(1)
-                                        .map(|s| s.parse().unwrap())
-                                        .collect();
-
-        let ways: u32 = times.iter().zip(dist.iter())
-                            .map(|(t, d)| {
-                                    let i: u32 = f32::ceil((*t as f32 - f32::sqrt(t.pow(2) as f32 - 4. * *d as f32)) / 2.) as u32;
+        let ways: u32 = races.times.iter().zip(races.dist.iter())
+                            .map(|(&t, &d)| {
+                                    let (t, d) = (t as u32, d as u32);
+                                    let i: u32 = f32::ceil((t as f32 - f32::sqrt(t.pow(2) as f32 - 4. * d as f32)) / 2.) as u32;
                                     t + 1 - 2 * i
                                 })
                             .product();
 
-        let d = ts.elapsed();
-
-        let mut output = out.lock().unwrap();
-        *output = ways.into();
-
-
-        Ok(d)
+        Ok(ways.into())
 
     }
 
-    fn part_b(&self, input: &str, out: std::sync::Arc<std::sync::Mutex<schema::Answer>>) 
-            -> Result<Duration, Box<(dyn std::error::Error + Send + Sync)>> {
-        let ts = Instant::now();
-        let mut lines = input.lines();
-
-        let time: u64 = lines.next().unwrap()
-                                        .split_ascii_whitespace()
-                                        .skip(1)
-                                        .flat_map(|s| s.chars())
-                                        .collect::<String>()
-                                        .parse::<u64>().unwrap();
-
-        let dist: u64 = lines.next().unwrap()
-                                        .split_ascii_whitespace()
-                                        .skip(1)
-                                        .flat_map(|s| s.chars())
-                                        .collect::<String>()
-                                        .parse::<u64>().unwrap();
+    fn part_b(&self, races: &Self::Input<'_>, _ctx: &Context) -> Result<Answer, BoxError> {
+        let time: u64 = kern(&races.times);
+
+        let dist: u64 = kern(&races.dist);
 
         let i1: u64 = f64::ceil((time as f64 - f64::sqrt(time.pow(2) as f64 - 4. * dist as f64)) / 2.) as u64;
         let i2: u64 = f64::floor((time as f64 + f64::sqrt(time.pow(2) as f64 - 4. * dist as f64)) / 2.) as u64;
         let ways = i2 - i1 + 1;
 
-        let d = ts.elapsed();
-
-        let mut output = out.lock().unwrap();
-        *output = ways.into();
-
-        Ok(d)
-    }
-
-    fn is_empty(&self) -> bool {
-        false
+        Ok(ways.into())
     }
 }
@@ -1,10 +1,10 @@
 use std::{
-    time::{Duration, Instant}, 
     collections::HashMap, 
     cmp::Ordering,
 };
 
-use schema::Solver;
+use indoc::indoc;
+use schema::{Answer, BoxError, Context, Example, solver, Solver};
 
 
 const CARDS: [char; 13] = ['2', '3', '4', '5', '6', '7', '8', '9', 'T', 'J', 'Q', 'K', 'A'];
@@ -22,7 +22,7 @@ enum Kind {
 }
 
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
-struct Hand<'a> {
+pub(crate) struct Hand<'a> {
 
     cards: &'a str,
 
@@ -194,18 +194,29 @@ impl PartialOrd for JokerHand<'_> {
     }
 }
 
-pub struct Day07;
+#[solver(year = 2023, day = 7)]
+pub(crate) struct Day07;
+
+const SET1: &str = indoc! {"
+    32T3K 765
+    T55J5 684
+    KK677 28
+    KTJJT 220
+    QQQJA 483
+"};
 
 impl Solver for Day07 {
+    type Input<'a> = Hands<'a>;
 
     fn title(&self) -> &'static str {
         r"Camel Cards"
     }
 
-    fn part_a(&self, input: &str, out: std::sync::Arc<std::sync::Mutex<schema::Answer>>) 
-            -> Result<Duration, Box<(dyn std::error::Error + Send + Sync)>> {
+    fn examples(&self) -> &'static [Example] {
+        const { &[Example::new("set1", SET1).part_a("6440").part_b("5905")] }
+    }
 
-        let ts = Instant::now();
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Input<'a>, BoxError> {
         let mut hands: Hands = Hands::default();
 
         for l in input.lines() {
@@ -214,78 +225,51 @@ impl Solver for Day07 {
             hands.push(Hand { cards: play[0], bid: play[1].parse::<u32>().unwrap() });
         }
 
-        hands.sort();
-
-        let total: u32 = hands.iter().enumerate().map(|(i, hand)| hand.bid * (i as u32 + 1)).sum();
+        Ok(hands)
+    }
 
-        let d = ts.elapsed();
+    fn part_a(&self, input: &Self::Input<'_>, _ctx: &Context) -> Result<Answer, BoxError> {
 
-        let mut output = out.lock().unwrap();
-        *output = total.into();
+        let mut hands: Hands = input.clone();
 
-        Ok(d)
+        hands.sort();
 
-    }
+        let total: u32 = hands.iter().enumerate().map(|(i, hand)| hand.bid * (i as u32 + 1)).sum();
 
-    fn part_b(&self, input: &str, out: std::sync::Arc<std::sync::Mutex<schema::Answer>>) 
-            -> Result<Duration, Box<(dyn std::error::Error + Send + Sync)>> {
+        Ok(total.into())
 
-        let ts = Instant::now();
-        let mut hands: JokerHands = JokerHands::default();
+    }
 
-        for l in input.lines() {
-            let play: Vec<&str> = l.split_whitespace().collect();
+    fn part_b(&self, input: &Self::Input<'_>, _ctx: &Context) -> Result<Answer, BoxError> {
 
-            hands.push(JokerHand { cards: play[0], bid: play[1].parse::<u32>().unwrap() });
-        }
+        let mut hands: JokerHands = input.iter()
+                                         .map(|&Hand { cards, bid }| JokerHand { cards, bid })
+                                         .collect();
 
         hands.sort();
 
         let total: u32 = hands.iter().enumerate().map(|(i, hand)| hand.bid * (i as u32 + 1)).sum();
 
-        let d = ts.elapsed();
-
-        let mut output = out.lock().unwrap();
-        *output = total.into();
-
-        Ok(d)
+        Ok(total.into())
 
     }
-
-    fn is_empty(&self) -> bool {
-        false
-    }
 }
 
 #[cfg(test)]
 mod test {
-    use std::{sync::Arc, sync::Mutex, ops::Deref};
-
     use schema::*;
 
-    use indoc::indoc;
-
-    use super::Day07;
-
-    const SET1: &str = indoc! {"
-        32T3K 765
-        T55J5 684
-        KK677 28
-        KTJJT 220
-        QQQJA 483
-    "};
+    use super::{Day07, SET1};
 
     #[test]
     fn part_a() {
-        let out = Arc::new(Mutex::new(Answer::Unimplemented));
-        let _ = Day07.part_a(SET1, out.clone());
-        assert_eq!(*out.lock().unwrap().deref(), Answer::Number(6440));
+        let input = Day07.parse(SET1).unwrap();
+        assert_eq!(Day07.part_a(&input, &Context::default()).unwrap(), Answer::Number(6440));
     }
 
     #[test]
     fn part_b() {
-        let out = Arc::new(Mutex::new(Answer::Unimplemented));
-        let _ = Day07.part_b(SET1, out.clone());
-        assert_eq!(*out.lock().unwrap().deref(), Answer::Number(5905));
+        let input = Day07.parse(SET1).unwrap();
+        assert_eq!(Day07.part_b(&input, &Context::default()).unwrap(), Answer::Number(5905));
     }
 }
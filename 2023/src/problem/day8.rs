@@ -1,10 +1,10 @@
-use std::time::{Duration, Instant};
 use ahash::{HashMap, HashMapExt};
 use num::integer::lcm;
 
-use schema::Solver;
+use indoc::indoc;
+use schema::{Answer, BoxError, Context, Example, solver, Solver};
 
-fn parse(input: &str) -> (Map, Vec<char>) {
+fn parse(input: &str) -> (Map<'_>, Vec<char>) {
     let mut lines = input.split('\n').filter(|l| !l.is_empty());
 
     let path: Vec<char> = lines.next().unwrap().chars().collect();
@@ -26,24 +26,74 @@ fn parse(input: &str) -> (Map, Vec<char>) {
 
 type Map<'a> = HashMap<&'a str, (&'a str, &'a str)>;
 
-pub struct Day08;
+#[solver(year = 2023, day = 8)]
+pub(crate) struct Day08;
+
+const PATH1: &str = indoc! {"
+    RL
+
+    AAA = (BBB, CCC)
+    BBB = (DDD, EEE)
+    CCC = (ZZZ, GGG)
+    DDD = (DDD, DDD)
+    EEE = (EEE, EEE)
+    GGG = (GGG, GGG)
+    ZZZ = (ZZZ, ZZZ)
+
+"};
+
+const PATH2: &str = indoc! {"
+    LLR
+
+    AAA = (BBB, BBB)
+    BBB = (AAA, ZZZ)
+    ZZZ = (ZZZ, ZZZ)
+
+"};
+
+const PATHB: &str = indoc! {"
+    LR
+
+    11A = (11B, XXX)
+    11B = (XXX, 11Z)
+    11Z = (11B, XXX)
+    22A = (22B, XXX)
+    22B = (22C, 22C)
+    22C = (22Z, 22Z)
+    22Z = (22B, 22B)
+    XXX = (XXX, XXX)
+
+"};
 
 impl Solver for Day08 {
+    type Input<'a> = (Map<'a>, Vec<char>);
+
     fn title(&self) -> &'static str {
         r"Haunted Wasteland"
     }
 
-    fn part_a(&self, input: &str, out: std::sync::Arc<std::sync::Mutex<schema::Answer>>) 
-            -> Result<Duration, Box<(dyn std::error::Error + Send + Sync)>> {
-        
-        let ts = Instant::now();
+    fn examples(&self) -> &'static [Example] {
+        const {
+            &[
+                Example::new("path1", PATH1).part_a("2"),
+                Example::new("path2", PATH2).part_a("6"),
+                Example::new("pathb", PATHB).part_b("6"),
+            ]
+        }
+    }
+
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Input<'a>, BoxError> {
+        Ok(parse(input))
+    }
 
-        let (map, path) = parse(input);
+    fn part_a(&self, (map, path): &Self::Input<'_>, ctx: &Context) -> Result<Answer, BoxError> {
 
         let mut visited: Vec<&str> = Vec::new();
         let mut node = map.get_key_value("AAA").unwrap();
 
+        // Never ends if `ZZZ` can't be reached, so keep an ear out for a timeout.
         for direction in path.iter().cycle() {
+            ctx.check()?;
 
             if *node.0 == "ZZZ" {
                 break;
@@ -64,20 +114,10 @@ impl Solver for Day08 {
 
         let total = visited.len();
 
-        let d = ts.elapsed();
-
-        let mut output = out.lock().unwrap();
-        *output = total.into();
-
-        Ok(d)
+        Ok(total.into())
     }
 
-    fn part_b(&self, input: &str, out: std::sync::Arc<std::sync::Mutex<schema::Answer>>) 
-            -> Result<Duration, Box<(dyn std::error::Error + Send + Sync)>> {
-
-        let ts = Instant::now();
-
-        let (map, path) = parse(input);
+    fn part_b(&self, (map, path): &Self::Input<'_>, ctx: &Context) -> Result<Answer, BoxError> {
 
         let mut path_len = Vec::new();
 
@@ -90,6 +130,7 @@ impl Solver for Day08 {
             let mut node = start_node;
 
             for direction in path.iter().cycle() {
+                ctx.check()?;
 
                 if node.0.ends_with('Z') {
                     break;
@@ -128,83 +169,42 @@ impl Solver for Day08 {
 
         }
 
-        let d = ts.elapsed();
-
-        let mut output = out.lock().unwrap();
-        *output = _total.into();
-
-        Ok(d)
-    }
-
-    fn is_empty(&self) -> bool {
-        false
+        Ok(_total.into())
     }
 }
 
 #[cfg(test)]
 mod test {
-    use std::{sync::Arc, sync::Mutex, ops::Deref};
-
     use schema::*;
 
-    use indoc::indoc;
-
-    use super::Day08;
-
-    const PATH1: &str = indoc! {"
-        RL
-
-        AAA = (BBB, CCC)
-        BBB = (DDD, EEE)
-        CCC = (ZZZ, GGG)
-        DDD = (DDD, DDD)
-        EEE = (EEE, EEE)
-        GGG = (GGG, GGG)
-        ZZZ = (ZZZ, ZZZ)
-
-    "};
-
-    const PATH2: &str = indoc! {"
-        LLR
-
-        AAA = (BBB, BBB)
-        BBB = (AAA, ZZZ)
-        ZZZ = (ZZZ, ZZZ)
-    
-    "};
-
-    const PATHB: &str = indoc! {"
-        LR
-
-        11A = (11B, XXX)
-        11B = (XXX, 11Z)
-        11Z = (11B, XXX)
-        22A = (22B, XXX)
-        22B = (22C, 22C)
-        22C = (22Z, 22Z)
-        22Z = (22B, 22B)
-        XXX = (XXX, XXX)
-    
-    "};
+    use super::{Day08, PATH1, PATH2, PATHB};
 
     #[test]
     fn part_a1() {
-        let out = Arc::new(Mutex::new(Answer::Unimplemented));
-        let _ = Day08.part_a(PATH1, out.clone());
-        assert_eq!(*out.lock().unwrap().deref(), Answer::Number(2));
+        let input = Day08.parse(PATH1).unwrap();
+        assert_eq!(Day08.part_a(&input, &Context::default()).unwrap(), Answer::Number(2));
     }
 
     #[test]
     fn part_a2() {
-        let out = Arc::new(Mutex::new(Answer::Unimplemented));
-        let _ = Day08.part_a(PATH2, out.clone());
-        assert_eq!(*out.lock().unwrap().deref(), Answer::Number(6));
+        let input = Day08.parse(PATH2).unwrap();
+        assert_eq!(Day08.part_a(&input, &Context::default()).unwrap(), Answer::Number(6));
     }
 
     #[test]
     fn part_b() {
-        let out = Arc::new(Mutex::new(Answer::Unimplemented));
-        let _ = Day08.part_b(PATHB, out.clone());
-        assert_eq!(*out.lock().unwrap().deref(), Answer::Number(6));
+        let input = Day08.parse(PATHB).unwrap();
+        assert_eq!(Day08.part_b(&input, &Context::default()).unwrap(), Answer::Number(6));
+    }
+
+    #[test]
+    fn unreachable_end() {
+        let input = Day08.parse("L\n\nAAA = (BBB, BBB)\nBBB = (AAA, AAA)\nZZZ = (ZZZ, ZZZ)").unwrap();
+
+        let cancel = CancelToken::default();
+        cancel.cancel();
+
+        let err = Day08.part_a(&input, &Context::new(cancel)).unwrap_err();
+        assert!(err.is::<Cancelled>());
     }
 }
@@ -1,22 +1,30 @@
-use std::time::{Duration, Instant};
 use num::integer::binomial;
 
-use schema::Solver;
+use indoc::indoc;
+use schema::{Answer, BoxError, Context, Example, solver, Solver};
 
 
-pub struct Day09;
+#[solver(year = 2023, day = 9)]
+pub(crate) struct Day09;
+
+const EXP1: &str = indoc! {"
+    0 3 6 9 12 15
+    1 3 6 10 15 21
+    10 13 16 21 30 45
+"};
 
 impl Solver for Day09 {
+    type Input<'a> = Vec<Vec<i64>>;
     
     fn title(&self) -> &'static str {
         r"Mirage Maintenance"
     }
 
-    fn part_a(&self, input: &str, out: std::sync::Arc<std::sync::Mutex<schema::Answer>>) 
-            -> Result<Duration, Box<(dyn std::error::Error + Send + Sync)>> {
-        
-        let ts = Instant::now();
+    fn examples(&self) -> &'static [Example] {
+        const { &[Example::new("exp1", EXP1).part_a("114").part_b("2")] }
+    }
 
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Input<'a>, BoxError> {
         let lines: Vec<Vec<i64>> = input.lines()
                                         .map(|l| {
                                                     l.split_ascii_whitespace()
@@ -24,10 +32,16 @@ impl Solver for Day09 {
                                                     .collect::<Vec<i64>>()
                                                 }).collect();
 
+        Ok(lines)
+    }
+
+    fn part_a(&self, lines: &Self::Input<'_>, _ctx: &Context) -> Result<Answer, BoxError> {
+        
         let mut total = 0i64;
         for line in lines.iter() {
 
-            let mut sign = 1i64;
+            // The reading nearest the extrapolated one always gets a positive weight
+            let mut sign = if line.len() % 2 == 0 { -1i64 } else { 1i64 };
 
             let mut next = 0i64;
             for (i, val) in line.iter().enumerate() {
@@ -40,31 +54,17 @@ impl Solver for Day09 {
 
         }
 
-        let d = ts.elapsed();
-
-        let mut output = out.lock().unwrap();
-        *output = total.into();
-
-        Ok(d)
+        Ok(total.into())
 
     }
 
-    fn part_b(&self, input: &str, out: std::sync::Arc<std::sync::Mutex<schema::Answer>>) 
-            -> Result<Duration, Box<(dyn std::error::Error + Send + Sync)>> {
+    fn part_b(&self, lines: &Self::Input<'_>, _ctx: &Context) -> Result<Answer, BoxError> {
         
-        let ts = Instant::now();
-
-        let mut lines: Vec<Vec<i64>> = input.lines()
-                                        .map(|l| {
-                                                    l.split_ascii_whitespace()
-                                                    .map(|i| i.parse().unwrap())
-                                                    .collect::<Vec<i64>>()
-                                                }).collect();
-
         let mut total = 0i64;
-        for line in lines.iter_mut() {
+        for line in lines.iter() {
 
-            let mut sign = 1i64;
+            // The reading nearest the extrapolated one always gets a positive weight
+            let mut sign = if line.len() % 2 == 0 { -1i64 } else { 1i64 };
 
             let mut next = 0i64;
             for (i, val) in line.iter().rev().enumerate() {
@@ -77,41 +77,27 @@ impl Solver for Day09 {
 
         }
 
-        let d = ts.elapsed();
-
-        let mut output = out.lock().unwrap();
-        *output = total.into();
-
-        Ok(d)
-    }
-
-    fn is_empty(&self) -> bool {
-        false
+        Ok(total.into())
     }
 }
 
 
 #[cfg(test)]
 mod test {
-    use std::{sync::Arc, sync::Mutex, ops::Deref};
-
     use schema::*;
 
-    use indoc::indoc;
-
-    use super::Day09;
-
-    const EXP1: &str = indoc! {"
-        0 3 6 9 12 15
-        1 3 6 10 15 21
-        10 13 16 21 30 45
-    "};
+    use super::{Day09, EXP1};
 
     #[test]
     fn part_a() {
-        let out = Arc::new(Mutex::new(Answer::Unimplemented));
-        let _ = Day09.part_a(EXP1, out.clone());
-        assert_eq!(*out.lock().unwrap().deref(), Answer::Number(114));
+        let input = Day09.parse(EXP1).unwrap();
+        assert_eq!(Day09.part_a(&input, &Context::default()).unwrap(), Answer::Number(114));
+    }
+
+    #[test]
+    fn part_b() {
+        let input = Day09.parse(EXP1).unwrap();
+        assert_eq!(Day09.part_b(&input, &Context::default()).unwrap(), Answer::Number(2));
     }
 
 }
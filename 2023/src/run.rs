@@ -0,0 +1,395 @@
+//! `run`: solve many days in one go and summarise them.
+
+use std::{
+    fmt::{self, Write as _},
+    panic::{self, AssertUnwindSafe},
+    str::FromStr,
+    thread::{self, ScopedJoinHandle},
+    time::{Duration, Instant},
+};
+
+use schema::{alloc::Allocations, Answer, DynSolver, Year};
+
+use crate::{input::{self, Source}, isolate, output, runner::{self, Outcome, Part, RunOptions}};
+
+/// The days picked on the command line: `5`, `1..=10`, `3..7` or a list of
+/// those like `1,4,9..=12`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DaySet(pub Vec<u8>);
+
+impl FromStr for DaySet {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let num = |n: &str| n.trim().parse::<u8>().map_err(|_| format!("`{n}` isn't a day"));
+        let mut days = Vec::new();
+
+        for item in s.split(',') {
+            if let Some((lo, hi)) = item.split_once("..=") {
+                days.extend(num(lo)?..=num(hi)?);
+            } else if let Some((lo, hi)) = item.split_once("..") {
+                days.extend(num(lo)?..num(hi)?);
+            } else {
+                days.push(num(item)?);
+            }
+        }
+
+        days.sort_unstable();
+        days.dedup();
+
+        match days.is_empty() {
+            true => Err(format!("`{s}` doesn't contain any days")),
+            false => Ok(Self(days)),
+        }
+    }
+}
+
+/// What happened to one day.
+pub enum DayResult {
+    Ran {
+        /// `None` if nothing was parsed: every part came from the cache, or
+        /// every isolated part died first.
+        parse: Option<Duration>,
+        parts: Vec<(Part, Outcome)>,
+        /// The parts whose answers came from the cache.
+        cached: Vec<Part>,
+    },
+    /// The input file couldn't be read.
+    NoInput(String),
+    /// The input file didn't parse.
+    BadInput(String),
+    /// Parsing the input panicked, or something else outside the parts did;
+    /// holds the panic message.
+    Panicked(String),
+}
+
+pub struct DayReport {
+    pub day: u8,
+    pub title: &'static str,
+    pub result: DayResult,
+    /// Wall time for the whole day, reading the input included.
+    pub elapsed: Duration,
+}
+
+impl DayReport {
+    pub fn outcome(&self, part: Part) -> Option<&Outcome> {
+        match &self.result {
+            DayResult::Ran { parts, .. } => parts.iter().find(|(p, _)| *p == part).map(|(_, o)| o),
+            _ => None,
+        }
+    }
+
+    pub fn cached(&self, part: Part) -> bool {
+        matches!(&self.result, DayResult::Ran { cached, .. } if cached.contains(&part))
+    }
+}
+
+/// Read the input for `day` from `source` and run `parts` of it, in this
+/// process or [isolated](isolate), taking what it can from the cache, if
+/// there is one, and saving the rest there.
+pub fn run_day(year: &Year, source: &Source, day: u8, solver: &dyn DynSolver, parts: &[Part], opts: &RunOptions)
+        -> DayReport {
+    let start = Instant::now();
+    let _span = tracing::info_span!("day", year = year.number(), day, title = solver.title()).entered();
+
+    let result = match source.read(year, day) {
+        Err(e) => DayResult::NoInput(e),
+        Ok(input) => {
+            let key = opts.cache.map(|c| (c, input::hash(&input)));
+
+            let (mut done, missing) = match &key {
+                Some((cache, hash)) => cache.lookup(year.number(), day, hash, parts),
+                None => (Vec::new(), parts.to_vec()),
+            };
+
+            let cached: Vec<Part> = done.iter().map(|(p, _)| *p).collect();
+
+            let solved = match (missing.is_empty(), &opts.isolate) {
+                (true, _) => Ok((None, Vec::new())),
+                (false, Some(limits)) => isolate::run_parts(year.number(), day, &input, &missing, opts.timeout, limits)
+                    .map_err(DayResult::BadInput),
+                (false, None) => {
+                    // A parser that panics on a bad input mustn't take the other days with it.
+                    let parsed = tracing::info_span!("parse")
+                        .in_scope(|| panic::catch_unwind(AssertUnwindSafe(|| solver.prepare(&input))));
+
+                    match parsed {
+                        Ok(Ok(parsed)) => Ok((Some(parsed.elapsed), runner::run_parts(&parsed, &missing, opts))),
+                        Ok(Err(e)) => Err(DayResult::BadInput(e.to_string())),
+                        Err(payload) => Err(DayResult::Panicked(runner::panic_message(payload))),
+                    }
+                },
+            };
+
+            let parse = solved.map(|(parse, solved)| {
+                if let Some((cache, hash)) = &key {
+                    cache.store(year.number(), day, hash, &solved);
+                }
+
+                done.extend(solved);
+                parse
+            });
+
+            done.sort_by_key(|(p, _)| *p);
+
+            match parse {
+                Err(failed) => failed,
+                Ok(parse) => DayResult::Ran { parse, parts: done, cached },
+            }
+        },
+    };
+
+    DayReport { day, title: solver.title(), result, elapsed: start.elapsed() }
+}
+
+/// Wait for a thread running [`run_day`]. If it panicked anyway, the day
+/// still gets a report saying so.
+pub fn join_day(handle: ScopedJoinHandle<'_, DayReport>, day: u8, solver: &dyn DynSolver) -> DayReport {
+    let start = Instant::now();
+
+    handle.join().unwrap_or_else(|payload| DayReport {
+        day,
+        title: solver.title(),
+        result: DayResult::Panicked(runner::panic_message(payload)),
+        elapsed: start.elapsed(),
+    })
+}
+
+/// The registered, non-empty days of `year` in `days` (or all of them),
+/// and the days asked for that have no solver.
+pub fn select_days<'y>(year: &'y Year, days: Option<&DaySet>) -> (Vec<(u8, &'y dyn DynSolver)>, Vec<u8>) {
+    let wanted = |d: u8| days.is_none_or(|s| s.0.contains(&d));
+
+    let selected: Vec<(u8, &dyn DynSolver)> = year.days()
+                                                  .filter(|&(d, s)| wanted(d) && !s.is_empty())
+                                                  .collect();
+
+    let skipped = match days {
+        Some(set) => set.0.iter().copied().filter(|d| !selected.iter().any(|(s, _)| s == d)).collect(),
+        None => Vec::new(),
+    };
+
+    (selected, skipped)
+}
+
+/// Run the [selected](select_days) days, one after another or all at once.
+pub fn run_days(year: &Year, source: &Source, days: Option<&DaySet>, parallel: bool, opts: &RunOptions)
+        -> (Vec<DayReport>, Vec<u8>) {
+    let (selected, skipped) = select_days(year, days);
+
+    let reports = match parallel {
+        false => selected.iter().map(|&(d, s)| run_day(year, source, d, s, &[Part::A, Part::B], opts)).collect(),
+        true => thread::scope(|scope| {
+            let handles: Vec<_> = selected.iter()
+                                          .map(|&(d, s)| (d, s, scope.spawn(move || run_day(year, source, d, s, &[Part::A, Part::B], opts))))
+                                          .collect();
+
+            handles.into_iter().map(|(d, s, h)| join_day(h, d, s)).collect()
+        }),
+    };
+
+    (reports, skipped)
+}
+
+/// One table cell for a part: the answer, or what went wrong. Answers from
+/// the cache are starred.
+fn cell(report: &DayReport, part: Part) -> String {
+    let star = match report.cached(part) {
+        true => " *",
+        false => "",
+    };
+
+    match report.outcome(part) {
+        Some(Outcome::Solved(s)) => match &s.answer {
+            Answer::Grid(g) if g.ocr().is_none() => format!("<grid>{star}"),
+            a => format!("{a}{star}"),
+        },
+        Some(Outcome::Failed(_)) => "FAILED".to_string(),
+        Some(Outcome::Panicked(_)) => "PANICKED".to_string(),
+        Some(Outcome::Cancelled) => "TIMEOUT".to_string(),
+        Some(Outcome::Killed(_)) => "KILLED".to_string(),
+        None => "-".to_string(),
+    }
+}
+
+fn allocations(outcome: Option<&Outcome>) -> Option<Allocations> {
+    match outcome {
+        Some(Outcome::Solved(s)) => s.allocations,
+        _ => None,
+    }
+}
+
+/// A part's allocations and peak, when they were counted.
+fn memory(outcome: Option<&Outcome>) -> String {
+    match allocations(outcome) {
+        Some(a) => format!("  {:>10}  {:>10}", a.count, output::bytes(a.peak)),
+        None => format!("  {:>10}  {:>10}", "-", "-"),
+    }
+}
+
+fn time(outcome: Option<&Outcome>) -> String {
+    match outcome {
+        Some(Outcome::Solved(s)) => format!("{:.2?}", s.elapsed),
+        _ => "-".to_string(),
+    }
+}
+
+/// Render the summary table, the failures under it and a total line.
+pub fn summary(reports: &[DayReport], skipped: &[u8], wall: Duration) -> String {
+    let mut out = String::new();
+    write_summary(&mut out, reports, skipped, wall).expect("Writing to a String can't fail");
+    out
+}
+
+fn write_summary(out: &mut String, reports: &[DayReport], skipped: &[u8], wall: Duration) -> fmt::Result {
+    let title_w = reports.iter().map(|r| r.title.len()).max().unwrap_or(0).max(5);
+    let a_w = reports.iter().map(|r| cell(r, Part::A).len()).max().unwrap_or(0).max(6);
+    let b_w = reports.iter().map(|r| cell(r, Part::B).len()).max().unwrap_or(0).max(6);
+
+    // Only when the binary counts allocations.
+    let counted = reports.iter().any(|r| [Part::A, Part::B].iter().any(|&p| allocations(r.outcome(p)).is_some()));
+
+    let header = match counted {
+        true => format!("  {:>10}  {:>10}  {:>10}  {:>10}", "A allocs", "A peak", "B allocs", "B peak"),
+        false => String::new(),
+    };
+
+    writeln!(out, "{:>3}  {:<title_w$}  {:<a_w$}  {:<b_w$}  {:>10}  {:>10}  {:>10}  {:>10}{header}",
+             "Day", "Title", "Part A", "Part B", "Parse", "A time", "B time", "Total")?;
+
+    for r in reports {
+        let parse = match &r.result {
+            DayResult::Ran { parse: Some(parse), .. } => format!("{parse:.2?}"),
+            DayResult::Ran { parse: None, cached, .. } if !cached.is_empty() => "CACHED".to_string(),
+            DayResult::Ran { parse: None, .. } => "-".to_string(),
+            DayResult::NoInput(_) => "NO INPUT".to_string(),
+            DayResult::BadInput(_) => "BAD INPUT".to_string(),
+            DayResult::Panicked(_) => "PANICKED".to_string(),
+        };
+
+        let memory = match counted {
+            true => memory(r.outcome(Part::A)) + &memory(r.outcome(Part::B)),
+            false => String::new(),
+        };
+
+        writeln!(out, "{:>3}  {:<title_w$}  {:<a_w$}  {:<b_w$}  {parse:>10}  {:>10}  {:>10}  {:>10}{memory}",
+                 r.day, r.title,
+                 cell(r, Part::A), cell(r, Part::B),
+                 time(r.outcome(Part::A)), time(r.outcome(Part::B)),
+                 format!("{:.2?}", r.elapsed))?;
+    }
+
+    let (mut failed_days, mut failures, mut timeouts) = (0, 0, 0);
+
+    for r in reports {
+        match &r.result {
+            DayResult::NoInput(e) => writeln!(out, "\nDay {}: {e}", r.day)?,
+            DayResult::BadInput(e) => writeln!(out, "\nDay {}: could not parse input:\n{e}", r.day)?,
+            DayResult::Panicked(m) => writeln!(out, "\nDay {}: panicked: {m}", r.day)?,
+            DayResult::Ran { parts, .. } => {
+                for (part, o) in parts {
+                    match o {
+                        Outcome::Failed(e) => writeln!(out, "\nDay {} part {part} failed: {e}", r.day)?,
+                        Outcome::Panicked(m) => writeln!(out, "\nDay {} part {part} panicked: {m}", r.day)?,
+                        Outcome::Killed(why) => writeln!(out, "\nDay {} part {part} was killed: {why}", r.day)?,
+                        Outcome::Cancelled => {
+                            timeouts += 1;
+                            continue;
+                        },
+                        Outcome::Solved(_) => continue,
+                    }
+
+                    failures += 1;
+                }
+
+                continue;
+            },
+        }
+
+        failed_days += 1;
+    }
+
+    if !skipped.is_empty() {
+        let list: Vec<String> = skipped.iter().map(u8::to_string).collect();
+        writeln!(out, "\nNo solver for day(s) {}", list.join(", "))?;
+    }
+
+    if reports.iter().any(|r| matches!(&r.result, DayResult::Ran { cached, .. } if !cached.is_empty())) {
+        writeln!(out, "\n* From the cache, with the time it took then; `--no-cache` solves them again")?;
+    }
+
+    write!(out, "\n{} day(s), {failed_days} failed day(s), {failures} failed part(s), {timeouts} timed out, took {wall:.2?}",
+           reports.len())
+}
+
+#[cfg(test)]
+mod test {
+    use schema::{registry::Registry, Answer, BoxError, Context, Solver};
+
+    use std::time::Duration;
+
+    use super::{run_day, summary, DayReport, DayResult, DaySet};
+    use crate::{input::Source, runner::{Part, RunOptions}};
+
+    struct Fragile;
+
+    impl Solver for Fragile {
+        type Input<'a> = u64;
+
+        fn title(&self) -> &'static str {
+            r"Fragile"
+        }
+
+        fn parse(&self, input: &str) -> Result<u64, BoxError> {
+            Ok(input.trim().parse().unwrap())
+        }
+
+        fn part_a(&self, input: &u64, _ctx: &Context) -> Result<Answer, BoxError> {
+            Ok(Answer::Number(*input))
+        }
+
+        fn part_b(&self, input: &u64, _ctx: &Context) -> Result<Answer, BoxError> {
+            Ok(Answer::Number(*input))
+        }
+    }
+
+    #[test]
+    fn day_sets() {
+        assert_eq!("5".parse(), Ok(DaySet(vec![5])));
+        assert_eq!("1..=3".parse(), Ok(DaySet(vec![1, 2, 3])));
+        assert_eq!("3..5,1".parse(), Ok(DaySet(vec![1, 3, 4])));
+        assert!("5..1".parse::<DaySet>().is_err());
+        assert!("x".parse::<DaySet>().is_err());
+    }
+
+    #[test]
+    fn parse_panics() {
+        let root = std::env::temp_dir().join(format!("aoc-run-test-{}", std::process::id()));
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::write(root.join("input"), "12x\n").unwrap();
+
+        let registry = Registry::load().unwrap();
+        let year = registry.year(2023).unwrap();
+        let source = Source::File(root.join("input"));
+
+        let report = run_day(year, &source, 1, &Fragile, &[Part::A, Part::B], &RunOptions::default());
+        assert!(matches!(&report.result, DayResult::Panicked(m) if m.contains("InvalidDigit")));
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn counts_failed_days() {
+        let report = |day, result| DayReport { day, title: "Fragile", result, elapsed: Duration::ZERO };
+
+        let reports = [
+            report(1, DayResult::BadInput("line 1: expected a number".to_string())),
+            report(2, DayResult::NoInput("no input".to_string())),
+            report(3, DayResult::Ran { parse: None, parts: Vec::new(), cached: Vec::new() }),
+        ];
+
+        let out = summary(&reports, &[], Duration::ZERO);
+        assert!(out.contains("Day 1: could not parse input:\nline 1: expected a number"));
+        assert!(out.ends_with("3 day(s), 2 failed day(s), 0 failed part(s), 0 timed out, took 0.00ns"));
+    }
+}
@@ -0,0 +1,243 @@
+use std::{
+    any::Any,
+    io::{IsTerminal, Write},
+    sync::{atomic::{AtomicU64, AtomicUsize, Ordering}, Arc},
+    thread,
+    time::{Duration, Instant},
+};
+
+use schema::{BoxError, CancelToken, Cancelled, Context, Parsed, Solution};
+
+use crate::{cache::Cache, isolate::Limits};
+
+/// How often the watchdog wakes to check the clock and redraw progress.
+const TICK: Duration = Duration::from_millis(50);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Part {
+    A,
+    B,
+}
+
+impl std::fmt::Display for Part {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Part::A => write!(f, "A"),
+            Part::B => write!(f, "B"),
+        }
+    }
+}
+
+impl std::str::FromStr for Part {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "a" | "1" => Ok(Part::A),
+            "b" | "2" => Ok(Part::B),
+            _ => Err(format!("`{s}` isn't a part; expected a or b")),
+        }
+    }
+}
+
+/// How a single part ended. One part failing never stops the other.
+pub enum Outcome {
+    Solved(Solution),
+    /// The solver returned an error.
+    Failed(BoxError),
+    /// Stopped by the timeout.
+    Cancelled,
+    /// The solver panicked; holds the panic message.
+    Panicked(String),
+    /// The part's [isolated](crate::isolate) process died before it could
+    /// say how it went, e.g. over its memory limit; holds why.
+    Killed(String),
+}
+
+impl Outcome {
+    pub fn from_join(joined: thread::Result<Result<Solution, BoxError>>) -> Self {
+        match joined {
+            Ok(Ok(s)) => Outcome::Solved(s),
+            Ok(Err(e)) if e.is::<Cancelled>() => Outcome::Cancelled,
+            Ok(Err(e)) => Outcome::Failed(e),
+            Err(payload) => Outcome::Panicked(panic_message(payload)),
+        }
+    }
+}
+
+pub fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    match payload.downcast::<String>() {
+        Ok(s) => *s,
+        Err(payload) => match payload.downcast::<&str>() {
+            Ok(s) => s.to_string(),
+            Err(_) => "unknown panic".to_string(),
+        },
+    }
+}
+
+/// Counts a part as finished and unparks the watchdog when dropped.
+struct Wake<'a>(&'a AtomicUsize, thread::Thread);
+
+impl Drop for Wake<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_add(1, Ordering::Release);
+        self.1.unpark();
+    }
+}
+
+/// Knobs for [`run_parts`].
+#[derive(Clone, Copy, Default)]
+pub struct RunOptions<'a> {
+    /// Cancel parts still running after this long.
+    pub timeout: Option<Duration>,
+    /// Draw progress bars on stderr (only if it's a terminal).
+    pub progress: bool,
+    /// Answers to reuse instead of solving, for
+    /// [`run_day`](crate::run::run_day).
+    pub cache: Option<&'a Cache>,
+    /// Run each part in its own process with these limits, for
+    /// [`run_day`](crate::run::run_day). `timeout` then kills it.
+    pub isolate: Option<Limits>,
+}
+
+/// Last `(done, total)` a part reported.
+#[derive(Default)]
+struct Progress {
+    done: AtomicU64,
+    total: AtomicU64,
+}
+
+impl Progress {
+    fn render(&self, part: Part) -> Option<String> {
+        const WIDTH: u64 = 20;
+
+        let total = self.total.load(Ordering::Relaxed);
+        let done = self.done.load(Ordering::Relaxed).min(total);
+
+        if total == 0 {
+            return None;
+        }
+
+        let filled = (done * WIDTH / total) as usize;
+
+        Some(format!("Part {part} [{}{}] {:>3}%",
+                     "#".repeat(filled),
+                     ".".repeat(WIDTH as usize - filled),
+                     done * 100 / total))
+    }
+}
+
+/// Run `parts` concurrently against `parsed`, catching errors and panics
+/// per part.
+///
+/// Parts still going after the timeout are cancelled through their
+/// [`Context`]; it's up to the solver to notice and return.
+pub fn run_parts(parsed: &Parsed<'_>, parts: &[Part], opts: &RunOptions)
+        -> Vec<(Part, Outcome)> {
+    let cancel = CancelToken::default();
+    let draw = opts.progress && std::io::stderr().is_terminal();
+
+    let waiter = thread::current();
+    let finished = AtomicUsize::new(0);
+
+    // So each part's span sits under whatever this is running for.
+    let parent = tracing::Span::current();
+
+    thread::scope(|s| {
+        let running: Vec<_> = parts.iter().map(|&part| {
+            let progress = Arc::new(Progress::default());
+            let sink = progress.clone();
+
+            let ctx = Context::new(cancel.clone()).with_progress(move |done, total| {
+                sink.done.store(done, Ordering::Relaxed);
+                sink.total.store(total, Ordering::Relaxed);
+            });
+
+            let waiter = waiter.clone();
+            let finished = &finished;
+            let parent = &parent;
+
+            let handle = s.spawn(move || {
+                // Wake the watchdog as soon as we finish, even by panicking.
+                let _wake = Wake(finished, waiter);
+                let _span = tracing::info_span!(parent: parent, "part", %part).entered();
+
+                match part {
+                    Part::A => parsed.part_a(&ctx),
+                    Part::B => parsed.part_b(&ctx),
+                }
+            });
+
+            (part, progress, handle)
+        }).collect();
+
+        let start = Instant::now();
+        let mut drawn = false;
+
+        while finished.load(Ordering::Acquire) < running.len() {
+            if opts.timeout.is_some_and(|t| start.elapsed() >= t) {
+                cancel.cancel();
+            }
+
+            if draw {
+                let bars: Vec<String> = running.iter()
+                                               .filter_map(|(part, p, _)| p.render(*part))
+                                               .collect();
+
+                if !bars.is_empty() {
+                    eprint!("\r{}", bars.join("  "));
+                    let _ = std::io::stderr().flush();
+                    drawn = true;
+                }
+            }
+
+            thread::park_timeout(TICK);
+        }
+
+        if drawn {
+            eprint!("\r\x1b[2K");
+        }
+
+        running.into_iter()
+               .map(|(part, _, h)| (part, Outcome::from_join(h.join())))
+               .collect()
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use schema::{Answer, BoxError, Context, Solver};
+
+    use super::{run_parts, Outcome, Part, RunOptions};
+
+    struct Flaky;
+
+    impl Solver for Flaky {
+        type Input<'a> = ();
+
+        fn title(&self) -> &'static str {
+            r"Flaky"
+        }
+
+        fn parse(&self, _input: &str) -> Result<(), BoxError> {
+            Ok(())
+        }
+
+        fn part_a(&self, _input: &(), _ctx: &Context) -> Result<Answer, BoxError> {
+            panic!("boom")
+        }
+
+        fn part_b(&self, _input: &(), _ctx: &Context) -> Result<Answer, BoxError> {
+            Ok(Answer::Number(2))
+        }
+    }
+
+    #[test]
+    fn isolates_panics() {
+        let parsed = schema::DynSolver::prepare(&Flaky, "").unwrap();
+        let results = run_parts(&parsed, &[Part::A, Part::B], &RunOptions::default());
+
+        assert!(matches!(&results[0], (Part::A, Outcome::Panicked(m)) if m == "boom"));
+        assert!(matches!(&results[1], (Part::B, Outcome::Solved(s)) if s.answer == Answer::Number(2)));
+    }
+}
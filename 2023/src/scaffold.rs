@@ -0,0 +1,232 @@
+//! `new`: start a day from a template.
+
+use std::path::{Path, PathBuf};
+
+use schema::BoxError;
+
+const TEMPLATE: &str = r####"use indoc::indoc;
+use schema::{Answer, BoxError, Context, Example, solver, Solver};
+
+
+#[solver(year = {{year}}, day = {{day}})]
+pub(crate) struct Day{{dd}};
+
+/// The example from the puzzle text.
+const EXAMPLE: &str = indoc! {"
+"};
+
+impl Solver for Day{{dd}} {
+    type Input<'a> = Vec<&'a str>;
+
+    fn title(&self) -> &'static str {
+        {{title}}
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        // Add `.part_a(..)` and `.part_b(..)` once the answers are known.
+        const { &[Example::new("example", EXAMPLE)] }
+    }
+
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Input<'a>, BoxError> {
+        Ok(input.lines().collect())
+    }
+
+    fn part_a(&self, _input: &Self::Input<'_>, _ctx: &Context) -> Result<Answer, BoxError> {
+        Ok(Answer::Unimplemented)
+    }
+
+    fn part_b(&self, _input: &Self::Input<'_>, _ctx: &Context) -> Result<Answer, BoxError> {
+        Ok(Answer::Unimplemented)
+    }
+
+    fn implemented(&self) -> (bool, bool) {
+        (false, false)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use schema::*;
+
+    use super::{Day{{dd}}, EXAMPLE};
+
+    #[test]
+    #[ignore = "no example answer yet"]
+    fn part_a() {
+        let input = Day{{dd}}.parse(EXAMPLE).unwrap();
+        assert_eq!(Day{{dd}}.part_a(&input, &Context::default()).unwrap(), Answer::Number(0));
+    }
+
+    #[test]
+    #[ignore = "no example answer yet"]
+    fn part_b() {
+        let input = Day{{dd}}.parse(EXAMPLE).unwrap();
+        assert_eq!(Day{{dd}}.part_b(&input, &Context::default()).unwrap(), Answer::Number(0));
+    }
+}
+"####;
+
+/// The source of a new, unsolved day.
+pub fn render(year: u16, day: u8, title: &str) -> String {
+    // A raw string, with enough `#`s that the title can't end it early.
+    let hashes = "#".repeat((0..).find(|&n| !title.contains(&format!("\"{}", "#".repeat(n)))).unwrap_or(0));
+
+    TEMPLATE.replace("{{year}}", &year.to_string())
+            .replace("{{day}}", &day.to_string())
+            .replace("{{dd}}", &format!("{day:02}"))
+            .replace("{{title}}", &format!("r{hashes}\"{title}\"{hashes}"))
+}
+
+/// `problem` with `mod day<N>;` added in day order, or `None` if it's
+/// already there.
+pub fn register(problem: &str, day: u8) -> Option<String> {
+    let module = |line: &str| line.strip_prefix("mod day")?.strip_suffix(';')?.parse::<u8>().ok();
+
+    if problem.lines().any(|l| module(l) == Some(day)) {
+        return None;
+    }
+
+    let mut lines: Vec<&str> = problem.lines().collect();
+    let new = format!("mod day{day};");
+
+    // After the last module before this day, or before the first one.
+    let at = match lines.iter().rposition(|l| module(l).is_some_and(|d| d < day)) {
+        Some(i) => i + 1,
+        None => lines.iter().position(|l| module(l).is_some()).unwrap_or(lines.len()),
+    };
+
+    lines.insert(at, &new);
+
+    Some(lines.join("\n") + "\n")
+}
+
+/// The year a day's `#[solver(year = .., day = ..)]` registers it under.
+fn solver_year(source: &str) -> Option<u16> {
+    let (_, rest) = source.split_once("#[solver(year = ")?;
+
+    rest[..rest.find(|c: char| !c.is_ascii_digit())?].parse().ok()
+}
+
+/// The nearest directory from `start` up that holds a year crate's
+/// `src/problem.rs`.
+pub fn find_crate(start: &Path) -> Option<PathBuf> {
+    start.ancestors()
+         .find(|dir| dir.join("src/problem.rs").is_file())
+         .map(Path::to_path_buf)
+}
+
+/// Write `src/problem/day<N>.rs` under `root` and add it to
+/// `src/problem.rs`, returning the new file.
+pub fn create(root: &Path, year: u16, day: u8, title: &str) -> Result<PathBuf, BoxError> {
+    let file = root.join("src/problem").join(format!("day{day}.rs"));
+    let problem = root.join("src/problem.rs");
+
+    if file.exists() {
+        return Err(format!("{} already exists", file.display()).into());
+    }
+
+    // One crate holds one year's days; don't slip another year's in.
+    let days = root.join("src/problem");
+    let existing = std::fs::read_dir(&days).map_err(|e| format!("{}: {e}", days.display()))?
+        .flatten()
+        .find_map(|d| solver_year(&std::fs::read_to_string(d.path()).ok()?));
+
+    if let Some(other) = existing.filter(|&y| y != year) {
+        return Err(format!("{} holds {other}'s days, not {year}'s; pass --dir", root.display()).into());
+    }
+
+    let registered = std::fs::read_to_string(&problem).map_err(|e| format!("{}: {e}", problem.display()))?;
+
+    let Some(registered) = register(&registered, day) else {
+        return Err(format!("{} already has a `mod day{day};`", problem.display()).into());
+    };
+
+    std::fs::write(&file, render(year, day, title))?;
+    std::fs::write(&problem, registered)?;
+
+    Ok(file)
+}
+
+#[cfg(test)]
+mod test {
+    use indoc::indoc;
+
+    use super::{find_crate, register, render, solver_year};
+
+    #[test]
+    fn registers_in_order() {
+        let problem = indoc! {"
+            //! Days.
+
+            mod day1;
+            mod day2;
+            mod day10;
+
+            #[cfg(test)]
+            mod test {}
+        "};
+
+        assert_eq!(register(problem, 3).unwrap(), indoc! {"
+            //! Days.
+
+            mod day1;
+            mod day2;
+            mod day3;
+            mod day10;
+
+            #[cfg(test)]
+            mod test {}
+        "});
+
+        assert!(register(problem, 2).is_none());
+        assert!(register(problem, 11).unwrap().contains("mod day10;\nmod day11;\n"));
+    }
+
+    #[test]
+    fn template() {
+        let day = render(2023, 7, "Camel \"Cards\"");
+
+        assert!(day.contains("#[solver(year = 2023, day = 7)]\npub(crate) struct Day07;"));
+        assert!(day.contains("r#\"Camel \"Cards\"\"#"));
+        assert!(!day.contains("{{"));
+    }
+
+    #[test]
+    fn matches_solver() {
+        let solver = include_str!("../schema/src/solver.rs");
+        let day = render(2023, 7, "Camel Cards");
+        let (day, _) = day.split_once("#[cfg(test)]").unwrap();
+
+        // Each item the template fills in, as the trait declares it: the
+        // associated type without its bounds, the parameters without `_`.
+        let items: Vec<_> = day.lines().map(str::trim).filter(|l| l.starts_with("fn ") || l.starts_with("type ")).collect();
+        assert_eq!(items.len(), 7);
+
+        for line in items {
+            let decl = match line.split_once(" = ") {
+                Some((ty, _)) => ty.to_string(),
+                None => line.trim_end_matches(" {").replace("_input", "input").replace("_ctx", "ctx"),
+            };
+
+            assert!(solver.contains(&decl), "`{decl}` isn't in the Solver trait");
+        }
+    }
+
+    #[test]
+    fn finds_crate() {
+        let root = std::env::temp_dir().join(format!("aoc-scaffold-test-{}", std::process::id()));
+        std::fs::create_dir_all(root.join("src/problem")).unwrap();
+        std::fs::write(root.join("src/problem.rs"), "mod day1;\n").unwrap();
+
+        assert_eq!(find_crate(&root.join("src/problem")), Some(root.clone()));
+        assert_eq!(find_crate(&root), Some(root.clone()));
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn years() {
+        assert_eq!(solver_year(&render(2016, 3, "Squares With Three Sides")), Some(2016));
+        assert_eq!(solver_year("mod day1;\nmod day2;\n"), None);
+    }
+}
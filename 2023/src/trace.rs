@@ -0,0 +1,372 @@
+//! `--trace`: where the time goes, as spans around each day, its parse and
+//! parts, and whatever phases a solver marks with `debug_span!`.
+//!
+//! `tree` prints the spans nested with their durations once the command is
+//! done, `json` logs them as they close, and `chrome` writes a trace-event
+//! file for `chrome://tracing` or Perfetto. Parts run with `--isolate` trace
+//! in their own process, so only their day shows up.
+
+use std::{
+    cell::Cell,
+    fmt::Write as _,
+    fs::File,
+    path::{Path, PathBuf},
+    sync::{atomic::{AtomicU64, Ordering}, Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use clap::ValueEnum;
+use schema::BoxError;
+use serde_json::{json, Value};
+use tracing::{field::{Field, Visit}, span, Level, Subscriber};
+use tracing_subscriber::{
+    filter::Targets,
+    fmt::{format::FmtSpan, writer::BoxMakeWriter},
+    layer::{Context, SubscriberExt},
+    registry::LookupSpan,
+    util::SubscriberInitExt,
+    Layer,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum TraceFormat {
+    /// Nested spans with their durations, on stderr
+    Tree,
+    /// A JSON object per line as each span closes
+    Json,
+    /// A Chrome trace-event file
+    Chrome,
+}
+
+/// Where `chrome` writes if not told otherwise.
+pub const DEFAULT_CHROME_PATH: &str = "./trace.json";
+
+/// A span that has closed.
+#[derive(Debug, Clone)]
+struct Closed {
+    id: u64,
+    parent: Option<u64>,
+    name: &'static str,
+    fields: Vec<(&'static str, String)>,
+    thread: u64,
+    /// Since tracing started.
+    start: Duration,
+    elapsed: Duration,
+}
+
+/// What's known about a span until it closes; kept in its extensions.
+struct Open {
+    id: u64,
+    parent: Option<u64>,
+    fields: Vec<(&'static str, String)>,
+    thread: u64,
+    start: Option<Instant>,
+    end: Option<Instant>,
+}
+
+#[derive(Default)]
+struct Fields(Vec<(&'static str, String)>);
+
+impl Visit for Fields {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.push((field.name(), value.to_string()));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.0.push((field.name(), format!("{value:?}")));
+    }
+}
+
+static THREADS: AtomicU64 = AtomicU64::new(0);
+
+thread_local! {
+    /// A small number for this thread, for trace viewers to group by.
+    static THREAD: Cell<u64> = const { Cell::new(0) };
+}
+
+fn thread() -> u64 {
+    THREAD.with(|t| {
+        if t.get() == 0 {
+            t.set(THREADS.fetch_add(1, Ordering::Relaxed) + 1);
+        }
+
+        t.get()
+    })
+}
+
+/// Keeps every span that closes, for [`tree`] and [`chrome`].
+struct Collector {
+    epoch: Instant,
+    next: AtomicU64,
+    closed: Mutex<Vec<Closed>>,
+}
+
+impl Collector {
+    fn new() -> Self {
+        Self { epoch: Instant::now(), next: AtomicU64::new(1), closed: Mutex::new(Vec::new()) }
+    }
+}
+
+/// The [`Layer`] side of a [`Collector`].
+struct Recorder(Arc<Collector>);
+
+impl<S: Subscriber + for<'a> LookupSpan<'a>> Layer<S> for Recorder {
+    fn on_new_span(&self, attrs: &span::Attributes<'_>, id: &span::Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else { return };
+
+        let mut fields = Fields::default();
+        attrs.record(&mut fields);
+
+        let parent = span.parent().and_then(|p| p.extensions().get::<Open>().map(|o| o.id));
+
+        span.extensions_mut().insert(Open {
+            id: self.0.next.fetch_add(1, Ordering::Relaxed),
+            parent,
+            fields: fields.0,
+            thread: thread(),
+            start: None,
+            end: None,
+        });
+    }
+
+    fn on_record(&self, id: &span::Id, values: &span::Record<'_>, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else { return };
+
+        let mut fields = Fields::default();
+        values.record(&mut fields);
+
+        let mut extensions = span.extensions_mut();
+
+        if let Some(open) = extensions.get_mut::<Open>() {
+            open.fields.extend(fields.0);
+        }
+    }
+
+    fn on_enter(&self, id: &span::Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else { return };
+
+        let mut extensions = span.extensions_mut();
+
+        if let Some(open) = extensions.get_mut::<Open>() {
+            open.start.get_or_insert_with(Instant::now);
+        }
+    }
+
+    fn on_exit(&self, id: &span::Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else { return };
+
+        let mut extensions = span.extensions_mut();
+
+        if let Some(open) = extensions.get_mut::<Open>() {
+            open.end = Some(Instant::now());
+        }
+    }
+
+    fn on_close(&self, id: span::Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else { return };
+        let Some(open) = span.extensions_mut().remove::<Open>() else { return };
+
+        // Never entered, so it didn't take any time.
+        let (Some(start), Some(end)) = (open.start, open.end) else { return };
+
+        self.0.closed.lock().unwrap().push(Closed {
+            id: open.id,
+            parent: open.parent,
+            name: span.name(),
+            fields: open.fields,
+            thread: open.thread,
+            start: start.duration_since(self.0.epoch),
+            elapsed: end.duration_since(start),
+        });
+    }
+}
+
+/// The spans as an indented tree, children in the order they started.
+fn tree(spans: &[Closed]) -> String {
+    fn write(out: &mut String, spans: &[Closed], span: &Closed, depth: usize) {
+        let fields: Vec<String> = span.fields.iter().map(|(k, v)| format!("{k}={v}")).collect();
+
+        let _ = match fields.is_empty() {
+            true => writeln!(out, "{:depth$}{} {:.2?}", "", span.name, span.elapsed, depth = depth * 2),
+            false => writeln!(out, "{:depth$}{}{{{}}} {:.2?}", "", span.name, fields.join(" "), span.elapsed, depth = depth * 2),
+        };
+
+        for child in spans.iter().filter(|s| s.parent == Some(span.id)) {
+            write(out, spans, child, depth + 1);
+        }
+    }
+
+    let mut sorted = spans.to_vec();
+    sorted.sort_by_key(|s| s.start);
+
+    let mut out = String::new();
+
+    // Anything whose parent wasn't kept is a root too.
+    for root in sorted.iter().filter(|s| s.parent.is_none_or(|p| !sorted.iter().any(|o| o.id == p))) {
+        write(&mut out, &sorted, root, 0);
+    }
+
+    out
+}
+
+/// The spans as Chrome trace events.
+fn chrome(spans: &[Closed]) -> Value {
+    let events: Vec<Value> = spans.iter().map(|s| {
+        let args: serde_json::Map<String, Value> = s.fields.iter()
+                                                       .map(|(k, v)| (k.to_string(), Value::from(v.as_str())))
+                                                       .collect();
+
+        json!({
+            "name": s.name,
+            "cat": "aoc",
+            "ph": "X",
+            "ts": s.start.as_nanos() as f64 / 1000.0,
+            "dur": s.elapsed.as_nanos() as f64 / 1000.0,
+            "pid": std::process::id(),
+            "tid": s.thread,
+            "args": args,
+        })
+    }).collect();
+
+    json!({ "traceEvents": events, "displayTimeUnit": "ns" })
+}
+
+/// Writes out what was traced when dropped, at the end of the command.
+pub struct Trace {
+    format: TraceFormat,
+    path: Option<PathBuf>,
+    collector: Option<Arc<Collector>>,
+}
+
+impl Drop for Trace {
+    fn drop(&mut self) {
+        let Some(collector) = &self.collector else { return };
+        let spans = collector.closed.lock().unwrap();
+
+        let written = match self.format {
+            TraceFormat::Tree => match &self.path {
+                Some(p) => std::fs::write(p, tree(&spans)).map_err(|e| format!("{}: {e}", p.display())),
+                None => {
+                    eprint!("{}", tree(&spans));
+                    Ok(())
+                },
+            },
+            TraceFormat::Chrome => {
+                let path = self.path.clone().unwrap_or_else(|| PathBuf::from(DEFAULT_CHROME_PATH));
+
+                std::fs::write(&path, chrome(&spans).to_string())
+                    .map(|()| eprintln!("Wrote {} span(s) to {}", spans.len(), path.display()))
+                    .map_err(|e| format!("{}: {e}", path.display()))
+            },
+            TraceFormat::Json => Ok(()),
+        };
+
+        if let Err(e) = written {
+            eprintln!("[WARN] Couldn't write the trace: {e}");
+        }
+    }
+}
+
+/// Start tracing this binary's spans in `format`, to `path` or the
+/// format's default.
+pub fn init(format: TraceFormat, path: Option<&Path>) -> Result<Trace, BoxError> {
+    let targets = Targets::new().with_target(env!("CARGO_CRATE_NAME"), Level::DEBUG);
+
+    let collector = match format {
+        TraceFormat::Json => {
+            let writer = match path {
+                Some(p) => {
+                    let file = File::create(p).map_err(|e| format!("{}: {e}", p.display()))?;
+                    BoxMakeWriter::new(Mutex::new(file))
+                },
+                None => BoxMakeWriter::new(std::io::stderr),
+            };
+
+            let layer = tracing_subscriber::fmt::layer()
+                .json()
+                .with_span_events(FmtSpan::CLOSE)
+                .with_thread_ids(true)
+                .with_writer(writer);
+
+            tracing_subscriber::registry().with(layer.with_filter(targets)).try_init()?;
+            None
+        },
+        TraceFormat::Tree | TraceFormat::Chrome => {
+            let collector = Arc::new(Collector::new());
+
+            tracing_subscriber::registry().with(Recorder(collector.clone()).with_filter(targets)).try_init()?;
+            Some(collector)
+        },
+    };
+
+    Ok(Trace { format, path: path.map(Path::to_path_buf), collector })
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use tracing::{debug_span, info_span};
+    use tracing_subscriber::layer::SubscriberExt;
+
+    use super::{chrome, tree, Collector, Recorder};
+
+    #[test]
+    fn spans() {
+        let collector = Arc::new(Collector::new());
+        let subscriber = tracing_subscriber::registry().with(Recorder(collector.clone()));
+
+        tracing::subscriber::with_default(subscriber, || {
+            let day = info_span!("day", day = 5, title = "Seeds").entered();
+
+            info_span!("parse").in_scope(|| {});
+
+            let part = info_span!("part", part = %'A');
+            let parent = part.clone();
+
+            // `with_default` only covers this thread, unlike `init`.
+            let dispatch = tracing::dispatcher::get_default(Clone::clone);
+
+            // Spans carry over into threads the way `run_parts` passes them.
+            std::thread::scope(|s| {
+                s.spawn(move || tracing::dispatcher::with_default(&dispatch, || {
+                    let _part = parent.entered();
+
+                    for index in 0..2 {
+                        debug_span!("map", index).in_scope(|| {});
+                    }
+                }));
+            });
+
+            drop(part);
+            drop(day);
+
+            // Never entered.
+            let _ = info_span!("unused");
+        });
+
+        let spans = collector.closed.lock().unwrap();
+
+        let shape: Vec<String> = tree(&spans).lines()
+                                              .map(|l| l.rsplit_once(' ').unwrap().0.to_string())
+                                              .collect();
+
+        assert_eq!(shape, [
+            "day{day=5 title=Seeds}",
+            "  parse",
+            "  part{part=A}",
+            "    map{index=0}",
+            "    map{index=1}",
+        ]);
+
+        let trace = chrome(&spans);
+        let events = trace["traceEvents"].as_array().unwrap();
+
+        assert_eq!(events.len(), 5);
+        assert!(events.iter().all(|e| e["ph"] == "X" && e["dur"].as_f64().is_some()));
+        assert!(events.iter().any(|e| e["name"] == "day" && e["args"]["title"] == "Seeds"));
+
+        let tid = |name| events.iter().find(|e| e["name"] == name).unwrap()["tid"].clone();
+        assert_ne!(tid("day"), tid("map"));
+    }
+}
@@ -0,0 +1,203 @@
+//! `verify`: check a run against the [answers file](crate::answers).
+
+use std::fmt;
+
+use schema::Answer;
+use serde::Serialize;
+
+use crate::{answers::AnswerStore, output::Tabular, run::{DayReport, DayResult}, runner::{Outcome, Part}};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    Pass,
+    /// Solved, but not to the recorded answer.
+    Fail,
+    /// Solved, with nothing recorded to check against.
+    Missing,
+    /// The part hasn't been written yet, so there's nothing to check.
+    Unimplemented,
+    /// Didn't produce an answer at all.
+    Error,
+}
+
+impl Status {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Status::Pass => "PASS",
+            Status::Fail => "FAIL",
+            Status::Missing => "MISSING",
+            Status::Unimplemented => "UNIMPLEMENTED",
+            Status::Error => "ERROR",
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct Check {
+    pub year: u16,
+    pub day: u8,
+    pub title: &'static str,
+    pub part: String,
+    pub status: Status,
+    pub expected: Option<String>,
+    /// The answer, or what went wrong.
+    pub got: Option<String>,
+}
+
+impl Tabular for Check {
+    const HEADERS: &'static [&'static str] = &["year", "day", "title", "part", "status", "expected", "got"];
+
+    fn cells(&self) -> Vec<String> {
+        vec![
+            self.year.to_string(),
+            self.day.to_string(),
+            self.title.to_string(),
+            self.part.clone(),
+            self.status.label().to_string(),
+            self.expected.clone().unwrap_or_default(),
+            self.got.clone().unwrap_or_default(),
+        ]
+    }
+}
+
+/// Check both parts of `report` against the `expected` answers.
+pub fn check(year: u16, report: &DayReport, expected: impl Fn(Part) -> Option<Answer>) -> Vec<Check> {
+    [Part::A, Part::B].into_iter().map(|part| {
+        let expected = expected(part);
+
+        let (status, got) = match (&report.result, report.outcome(part)) {
+            (_, Some(Outcome::Solved(s))) => match &expected {
+                _ if matches!(s.answer, Answer::Unimplemented) => (Status::Unimplemented, s.answer.to_string()),
+                Some(e) if s.answer.matches(e) => (Status::Pass, s.answer.to_string()),
+                Some(_) => (Status::Fail, s.answer.to_string()),
+                None => (Status::Missing, s.answer.to_string()),
+            },
+            (_, Some(Outcome::Failed(e))) => (Status::Error, e.to_string()),
+            (_, Some(Outcome::Panicked(m))) => (Status::Error, format!("panicked: {m}")),
+            (_, Some(Outcome::Cancelled)) => (Status::Error, "timed out".to_string()),
+            (_, Some(Outcome::Killed(why))) => (Status::Error, why.clone()),
+            (DayResult::NoInput(e) | DayResult::BadInput(e), None) => (Status::Error, e.clone()),
+            (DayResult::Panicked(m), None) => (Status::Error, format!("panicked: {m}")),
+            (DayResult::Ran { .. }, None) => (Status::Error, "not run".to_string()),
+        };
+
+        Check {
+            year,
+            day: report.day,
+            title: report.title,
+            part: part.to_string(),
+            status,
+            expected: expected.map(|e| e.to_string()),
+            got: Some(got),
+        }
+    }).collect()
+}
+
+/// How many checks ended up with each [`Status`].
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Tally {
+    pub pass: usize,
+    pub fail: usize,
+    pub missing: usize,
+    pub unimplemented: usize,
+    pub error: usize,
+}
+
+impl Tally {
+    pub fn of<'a>(checks: impl IntoIterator<Item = &'a Check>) -> Self {
+        checks.into_iter().fold(Self::default(), |mut t, c| {
+            match c.status {
+                Status::Pass => t.pass += 1,
+                Status::Fail => t.fail += 1,
+                Status::Missing => t.missing += 1,
+                Status::Unimplemented => t.unimplemented += 1,
+                Status::Error => t.error += 1,
+            }
+
+            t
+        })
+    }
+
+    /// Nothing failed or errored; missing answers and unwritten parts are
+    /// fine.
+    pub fn ok(&self) -> bool {
+        self.fail + self.error == 0
+    }
+}
+
+impl fmt::Display for Tally {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} passed, {} failed, {} missing, {} unimplemented, {} error(s)",
+               self.pass, self.fail, self.missing, self.unimplemented, self.error)
+    }
+}
+
+/// Put every solved part in `parts` into `store` as `day`'s answers,
+/// returning how many.
+pub fn record(store: &mut AnswerStore, day: u8, parts: &[(Part, Outcome)]) -> usize {
+    parts.iter()
+         .filter(|(part, outcome)| match outcome {
+             Outcome::Solved(s) => store.set(day, *part, &s.answer),
+             _ => false,
+         })
+         .count()
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use schema::{Answer, Solution};
+
+    use super::{check, record, Status, Tally};
+    use crate::{answers::AnswerStore, run::{DayReport, DayResult}, runner::{Outcome, Part}};
+
+    fn report(a: Answer) -> DayReport {
+        let solved = |answer| Outcome::Solved(Solution::new(answer, Duration::ZERO));
+
+        DayReport {
+            day: 4,
+            title: "Test",
+            result: DayResult::Ran {
+                parse: Some(Duration::ZERO),
+                parts: vec![(Part::A, solved(a)), (Part::B, Outcome::Failed("boom".into()))],
+                cached: Vec::new(),
+            },
+            elapsed: Duration::ZERO,
+        }
+    }
+
+    #[test]
+    fn statuses() {
+        let mut store = AnswerStore::default();
+
+        let first = check(2023, &report(Answer::Number(13)), |p| store.get(4, p));
+        assert_eq!(first.iter().map(|c| c.status).collect::<Vec<_>>(), [Status::Missing, Status::Error]);
+
+        let DayResult::Ran { parts, .. } = report(Answer::Number(13)).result else { unreachable!() };
+        assert_eq!(record(&mut store, 4, &parts), 1);
+
+        let statuses = |a| check(2023, &report(a), |p| store.get(4, p)).into_iter().map(|c| c.status).collect::<Vec<_>>();
+        assert_eq!(statuses(Answer::from("13")), [Status::Pass, Status::Error]);
+        assert_eq!(statuses(Answer::Number(14)), [Status::Fail, Status::Error]);
+
+        let tally = Tally::of(&check(2023, &report(Answer::Number(13)), |p| store.get(4, p)));
+        assert_eq!(tally, Tally { pass: 1, fail: 0, missing: 0, unimplemented: 0, error: 1 });
+        assert!(!tally.ok());
+    }
+
+    #[test]
+    fn unimplemented() {
+        let mut report = report(Answer::Unimplemented);
+        let DayResult::Ran { parts, .. } = &mut report.result else { unreachable!() };
+        parts[1].1 = Outcome::Solved(Solution::new(Answer::Unimplemented, Duration::ZERO));
+
+        let checks = check(2023, &report, |_| Some(Answer::Number(13)));
+        assert!(checks.iter().all(|c| c.status == Status::Unimplemented));
+
+        let tally = Tally::of(&checks);
+        assert_eq!(tally.unimplemented, 2);
+        assert!(tally.ok());
+    }
+}